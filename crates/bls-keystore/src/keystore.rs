@@ -1,7 +1,8 @@
 //! Keystore JSON definition
 
+use crate::password_util::checksum;
+use crate::{KdfChoice, KeystoreError};
 use aes::cipher::{KeyIvInit, StreamCipher};
-use anyhow::{anyhow, bail, Result};
 use pbkdf2::pbkdf2_hmac;
 use scrypt::{scrypt, Params};
 use serde::{Deserialize, Serialize};
@@ -10,6 +11,10 @@ use std::collections::HashMap;
 
 type Aes128Ctr128BE = ctr::Ctr128BE<aes::Aes128>;
 
+const IV_LEN: usize = 16;
+/// Length of the decryption key, the checksum is computed from its second 16 bytes.
+const DKLEN: usize = 32;
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ChecksumModule {
     pub function: String,
@@ -33,33 +38,40 @@ pub struct CipherModule {
 }
 
 impl CipherModule {
-    pub fn decrypt_secret(&self, decryption_key: &[u8]) -> Result<Vec<u8>> {
-        if !self.function.eq_ignore_ascii_case("aes-128-ctr") {
-            bail!("Unsupported cipher function {}", self.function);
-        }
+    /// Encrypt secret with aes-128-ctr using the first 16 bytes of the decryption key.
+    pub fn encrypt_secret(
+        secret: &[u8],
+        decryption_key: &[u8],
+        iv: &[u8],
+    ) -> Result<Self, KeystoreError> {
+        let mut message = secret.to_vec();
+        aes_128_ctr(decryption_key, iv)?.apply_keystream(&mut message);
 
-        // for aes-128, the decryption key size must be >= 16
-        if decryption_key.len() < 16 {
-            bail!("Invalid decryption key length");
-        }
+        Ok(CipherModule {
+            function: "aes-128-ctr".to_string(),
+            params: CipherParams { iv: iv.to_vec() },
+            message,
+        })
+    }
 
-        let dk_slice = &decryption_key[0..16];
-        let iv = &self.params.iv[..];
-        let message = &self.message;
+    pub fn decrypt_secret(&self, decryption_key: &[u8]) -> Result<Vec<u8>, KeystoreError> {
+        if !self.function.eq_ignore_ascii_case("aes-128-ctr") {
+            return Err(KeystoreError::UnsupportedCipher(self.function.clone()));
+        }
 
-        let mut buf = vec![0; message.len()];
-        let cipher_result = Aes128Ctr128BE::new_from_slices(dk_slice, iv);
-        let mut cipher = match cipher_result {
-            Ok(cipher) => cipher,
-            Err(err) => bail!("Error creating cipher: {}", err),
-        };
+        let mut secret = self.message.clone();
+        aes_128_ctr(decryption_key, &self.params.iv)?.apply_keystream(&mut secret);
+        Ok(secret)
+    }
+}
 
-        let decrypt_result = cipher.apply_keystream_b2b(message, &mut buf);
-        match decrypt_result {
-            Ok(()) => Ok(buf),
-            Err(err) => bail!("Error applying cipher: {}", err),
-        }
+fn aes_128_ctr(decryption_key: &[u8], iv: &[u8]) -> Result<Aes128Ctr128BE, KeystoreError> {
+    if iv.len() != IV_LEN {
+        return Err(KeystoreError::InvalidIvLength(iv.len()));
     }
+    // for aes-128, the decryption key size must be >= 16, the kdf output is at least DKLEN
+    Ok(Aes128Ctr128BE::new_from_slices(&decryption_key[0..16], iv)
+        .expect("aes-128-ctr takes a 16 bytes key and IV"))
 }
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
@@ -79,7 +91,57 @@ pub enum KdfParams {
 }
 
 impl KdfParams {
-    pub fn decryption_key(&self, normalized_password: &str) -> Result<Vec<u8>> {
+    pub fn new(kdf_choice: &KdfChoice, salt: &[u8]) -> Result<Self, KeystoreError> {
+        match *kdf_choice {
+            KdfChoice::Scrypt { n, r, p } => {
+                if n < 2 || !n.is_power_of_two() {
+                    return Err(KeystoreError::InvalidKdfParams(format!(
+                        "scrypt parameter n must be a power of 2 greater than 1: {}",
+                        n
+                    )));
+                }
+                Ok(KdfParams::SCrypt {
+                    params: SCryptParams {
+                        dklen: DKLEN,
+                        n,
+                        p,
+                        r,
+                        salt: salt.to_vec(),
+                    },
+                    message: "".to_string(),
+                })
+            }
+            KdfChoice::Pbkdf2 { c } => Ok(KdfParams::PbKdf2 {
+                params: Pbkdf2Params {
+                    dklen: DKLEN,
+                    c,
+                    prf: "hmac-sha256".to_string(),
+                    salt: salt.to_vec(),
+                },
+                message: "".to_string(),
+            }),
+        }
+    }
+
+    /// The function and cost parameters of the KDF. Only the hmac-sha256 prf of pbkdf2 can be
+    /// chosen.
+    pub fn kdf_choice(&self) -> Result<KdfChoice, KeystoreError> {
+        match self {
+            KdfParams::SCrypt { params, .. } => Ok(KdfChoice::Scrypt {
+                n: params.n,
+                r: params.r,
+                p: params.p,
+            }),
+            KdfParams::PbKdf2 { params, .. } if params.prf == "hmac-sha256" => {
+                Ok(KdfChoice::Pbkdf2 { c: params.c })
+            }
+            KdfParams::PbKdf2 { params, .. } => {
+                Err(KeystoreError::UnsupportedPrf(params.prf.clone()))
+            }
+        }
+    }
+
+    pub fn decryption_key(&self, normalized_password: &str) -> Result<Vec<u8>, KeystoreError> {
         match self {
             KdfParams::SCrypt { params, message: _ } => params.decryption_key(normalized_password),
             KdfParams::PbKdf2 { params, message: _ } => params.decryption_key(normalized_password),
@@ -98,18 +160,19 @@ pub struct SCryptParams {
 }
 
 impl SCryptParams {
-    pub fn decryption_key(&self, password: &str) -> Result<Vec<u8>> {
-        let log_n = f64::log2(self.n as f64).round() as u8;
-        let param_result = Params::new(log_n, self.r, self.p, self.dklen);
-        let params = match param_result {
-            Ok(params) => params,
-            Err(err) => bail!("Error constructing Params {}", err.to_string()),
-        };
-        let mut result = vec![0u8; self.dklen];
-        let scrypt_result = scrypt(password.as_bytes(), &self.salt, &params, &mut result);
-        if let Err(err) = scrypt_result {
-            bail!("Error in scrypt method {}", err)
+    pub fn decryption_key(&self, password: &str) -> Result<Vec<u8>, KeystoreError> {
+        validate_dklen(self.dklen)?;
+        if self.n < 2 || !self.n.is_power_of_two() {
+            return Err(KeystoreError::InvalidKdfParams(format!(
+                "scrypt parameter n must be a power of 2 greater than 1: {}",
+                self.n
+            )));
         }
+        let params = Params::new(self.n.ilog2() as u8, self.r, self.p, self.dklen)
+            .map_err(|err| KeystoreError::InvalidKdfParams(format!("scrypt {}", err)))?;
+        let mut result = vec![0u8; self.dklen];
+        scrypt(password.as_bytes(), &self.salt, &params, &mut result)
+            .map_err(|err| KeystoreError::InvalidKdfParams(format!("scrypt {}", err)))?;
         Ok(result)
     }
 }
@@ -124,7 +187,8 @@ pub struct Pbkdf2Params {
 }
 
 impl Pbkdf2Params {
-    pub fn decryption_key(&self, password: &str) -> Result<Vec<u8>> {
+    pub fn decryption_key(&self, password: &str) -> Result<Vec<u8>, KeystoreError> {
+        validate_dklen(self.dklen)?;
         let mut result = vec![0u8; self.dklen];
         match self.prf.as_str() {
             "hmac-sha256" => {
@@ -133,12 +197,32 @@ impl Pbkdf2Params {
             "hmac-sha512" => {
                 pbkdf2_hmac::<Sha512>(password.as_bytes(), &self.salt, self.c, &mut result)
             }
-            _ => bail!("Unsupported prf for pbkdf2: {}", &self.prf),
+            _ => return Err(KeystoreError::UnsupportedPrf(self.prf.clone())),
         }
         Ok(result.to_vec())
     }
 }
 
+fn validate_dklen(dklen: usize) -> Result<(), KeystoreError> {
+    if dklen < DKLEN {
+        return Err(KeystoreError::InvalidKdfParams(format!(
+            "dklen {} is below {}",
+            dklen, DKLEN
+        )));
+    }
+    Ok(())
+}
+
+impl ChecksumModule {
+    pub fn new(decryption_key: &[u8], cipher_message: &[u8]) -> Self {
+        ChecksumModule {
+            function: "sha256".to_string(),
+            params: HashMap::new(),
+            message: checksum(decryption_key, cipher_message),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Crypto {
     pub kdf: KdfParams,
@@ -156,16 +240,21 @@ pub struct Keystore {
     pub version: u8,
 }
 
-pub fn parse_keystore(json: &str) -> Result<Keystore> {
-    let keystore: Keystore = serde_json::from_str(json)?;
-    if keystore.version != 4 {
-        Err(anyhow!(
-            "Keystore version {} is not supported",
-            keystore.version
-        ))
-    } else {
-        Ok(keystore)
+/// Parse a version 4 keystore. The version and kdf function are checked before the rest of the
+/// JSON, so they are reported as unsupported rather than invalid.
+pub fn parse_keystore(json: &str) -> Result<Keystore, KeystoreError> {
+    let keystore: serde_json::Value = serde_json::from_str(json)?;
+    if let Some(version) = keystore["version"].as_u64() {
+        if version != 4 {
+            return Err(KeystoreError::UnsupportedVersion(version));
+        }
+    }
+    if let Some(function) = keystore["crypto"]["kdf"]["function"].as_str() {
+        if !matches!(function, "scrypt" | "pbkdf2") {
+            return Err(KeystoreError::UnsupportedKdf(function.to_string()));
+        }
     }
+    Ok(serde_json::from_value(keystore)?)
 }
 
 #[cfg(test)]
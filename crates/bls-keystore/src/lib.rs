@@ -1,22 +1,92 @@
 //! Library to encrypt and decrypt BLS12-381 Keystores.
-//! The keystore is in JSON format as defined by [EIP-2335][1].
+//! The keystore is in JSON format as defined by [EIP-2335][1]. Keystores of validator keys can
+//! be generated from a [BIP-39][2] mnemonic.
 //!
 //![1]: https://github.com/ethereum/EIPs/blob/master/EIPS/eip-2335.md
+//![2]: https://github.com/bitcoin/bips/blob/master/bip-0039.mediawiki
 
 mod keystore;
+mod mnemonic;
 mod password_util;
 
 #[cfg(test)]
 mod tests;
 
+use crate::keystore::{ChecksumModule, CipherModule, Crypto, KdfParams, Keystore};
 use crate::{password_util::normalize_password, password_util::validate_decryption_key};
-use anyhow::{bail, Result};
+use bls::BlsError;
+use rand_core::{OsRng, RngCore};
+use thiserror::Error;
+
+pub use crate::mnemonic::{mnemonic_to_seed, SEED_LEN};
+
+#[derive(Error, Debug)]
+pub enum KeystoreError {
+    #[error("Invalid keystore JSON: {0}")]
+    InvalidJson(#[from] serde_json::Error),
+    #[error("Keystore version {0} is not supported")]
+    UnsupportedVersion(u64),
+    #[error("Unsupported kdf function {0}")]
+    UnsupportedKdf(String),
+    #[error("Unsupported prf for pbkdf2: {0}")]
+    UnsupportedPrf(String),
+    #[error("Unsupported cipher function {0}")]
+    UnsupportedCipher(String),
+    #[error("Unsupported checksum function {0}")]
+    UnsupportedChecksum(String),
+    #[error("Invalid kdf parameters: {0}")]
+    InvalidKdfParams(String),
+    #[error("Password verification failed")]
+    WrongPassword,
+    #[error("Invalid cipher IV length {0}, expected 16 bytes")]
+    InvalidIvLength(usize),
+    #[error("Keystore pubkey {0} does not match its secret key")]
+    PubkeyMismatch(String),
+    #[error("Keystore has no {0}")]
+    MissingField(&'static str),
+    #[error("Invalid mnemonic: {0}")]
+    InvalidMnemonic(String),
+    #[error("Validator index {start_index} + {count} is out of range")]
+    IndexOutOfRange { start_index: u32, count: u32 },
+    #[error(transparent)]
+    Bls(#[from] BlsError),
+}
+
+/// Key derivation function, with its cost parameters, used to encrypt a keystore.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KdfChoice {
+    Scrypt { n: u32, r: u32, p: u32 },
+    Pbkdf2 { c: u32 },
+}
+
+impl KdfChoice {
+    /// scrypt with the parameters recommended by EIP-2335
+    pub const SCRYPT: KdfChoice = KdfChoice::Scrypt {
+        n: 262144,
+        r: 8,
+        p: 1,
+    };
+    /// pbkdf2 (hmac-sha256) with the parameters recommended by EIP-2335
+    pub const PBKDF2: KdfChoice = KdfChoice::Pbkdf2 { c: 262144 };
+}
+
+impl Default for KdfChoice {
+    fn default() -> Self {
+        KdfChoice::SCRYPT
+    }
+}
 
 /// Decrypt BLS12-381 keystore with provided password. Returns decrypted key
-/// as Vec<u8>
-pub fn decrypt(keystore_json: &str, password: &str) -> Result<Vec<u8>> {
+/// as Vec<u8>, after checking it is the secret key of the keystore's `pubkey` when present.
+pub fn decrypt(keystore_json: &str, password: &str) -> Result<Vec<u8>, KeystoreError> {
     let normalized_password = normalize_password(password);
     let keystore = keystore::parse_keystore(keystore_json)?;
+    let checksum_function = &keystore.crypto.checksum.function;
+    if checksum_function != "sha256" {
+        return Err(KeystoreError::UnsupportedChecksum(
+            checksum_function.clone(),
+        ));
+    }
     let decryption_key = keystore
         .crypto
         .kdf
@@ -27,8 +97,144 @@ pub fn decrypt(keystore_json: &str, password: &str) -> Result<Vec<u8>> {
         &keystore.crypto.cipher.message,
         &keystore.crypto.checksum.message,
     ) {
-        bail!("Password verification failed");
+        return Err(KeystoreError::WrongPassword);
+    }
+
+    let secret = keystore.crypto.cipher.decrypt_secret(&decryption_key)?;
+    if let Some(pubkey) = &keystore.pubkey {
+        let derived = bls::SecretKey::from_bytes(&secret)?.public_key();
+        if bls::PublicKey::from_hex(pubkey)? != derived {
+            return Err(KeystoreError::PubkeyMismatch(pubkey.clone()));
+        }
     }
+    Ok(secret)
+}
+
+/// Encrypt BLS12-381 secret key with provided password. Returns the keystore JSON with a random
+/// salt, IV and UUID and the public key derived from the secret.
+pub fn encrypt(
+    secret: &[u8],
+    password: &str,
+    kdf_choice: KdfChoice,
+    path: &str,
+    description: Option<&str>,
+) -> Result<String, KeystoreError> {
+    let pubkey = bls::SecretKey::from_bytes(secret)?.public_key();
+
+    let keystore = Keystore {
+        crypto: encrypt_secret(secret, password, kdf_choice)?,
+        description: description.map(str::to_string),
+        pubkey: Some(hex::encode(pubkey.compress())),
+        path: path.to_string(),
+        uuid: uuid::Uuid::new_v4().to_string(),
+        version: 4,
+    };
+
+    Ok(serde_json::to_string_pretty(&keystore)?)
+}
+
+/// Re-encrypt a keystore, decrypted with `old_password`, with `new_password`. The KDF is
+/// `kdf_choice` if provided, or the KDF of the keystore with its cost parameters otherwise.
+/// The salt and IV are random, the `pubkey`, `path`, `description` and `uuid` of the keystore
+/// are preserved.
+pub fn change_password(
+    keystore_json: &str,
+    old_password: &str,
+    new_password: &str,
+    kdf_choice: Option<KdfChoice>,
+) -> Result<String, KeystoreError> {
+    let secret = decrypt(keystore_json, old_password)?;
+    let keystore = keystore::parse_keystore(keystore_json)?;
+    let kdf_choice = match kdf_choice {
+        Some(kdf_choice) => kdf_choice,
+        None => keystore.crypto.kdf.kdf_choice()?,
+    };
+
+    let keystore = Keystore {
+        crypto: encrypt_secret(&secret, new_password, kdf_choice)?,
+        ..keystore
+    };
+
+    Ok(serde_json::to_string_pretty(&keystore)?)
+}
+
+/// Crypto module of `secret` encrypted with `password`, with a random salt and IV.
+fn encrypt_secret(
+    secret: &[u8],
+    password: &str,
+    kdf_choice: KdfChoice,
+) -> Result<Crypto, KeystoreError> {
+    let mut salt = [0u8; 32];
+    OsRng.fill_bytes(&mut salt);
+    let mut iv = [0u8; 16];
+    OsRng.fill_bytes(&mut iv);
+
+    let kdf = KdfParams::new(&kdf_choice, &salt)?;
+    let decryption_key = kdf.decryption_key(normalize_password(password).as_str())?;
+    let cipher = CipherModule::encrypt_secret(secret, &decryption_key, &iv)?;
+    let checksum = ChecksumModule::new(&decryption_key, &cipher.message);
+
+    Ok(Crypto {
+        kdf,
+        checksum,
+        cipher,
+    })
+}
+
+/// Check the keystore's public key is the EIP-2333 key at its `path` of the key tree of `seed`.
+/// The keystore doesn't need to be decrypted.
+pub fn path_matches_seed(keystore_json: &str, seed: &[u8]) -> Result<bool, KeystoreError> {
+    let keystore = keystore::parse_keystore(keystore_json)?;
+    let Some(pubkey) = keystore.pubkey else {
+        return Err(KeystoreError::MissingField("pubkey"));
+    };
+    if keystore.path.is_empty() {
+        return Err(KeystoreError::MissingField("derivation path"));
+    }
+    let path: bls::DerivationPath = keystore.path.parse()?;
+    let derived = bls::SecretKey::derive(seed, &path)?.public_key();
+    Ok(bls::PublicKey::from_hex(&pubkey)? == derived)
+}
+
+/// Keystore of a validator signing key generated from a mnemonic.
+#[derive(Debug)]
+pub struct GeneratedKeystore {
+    pub path: bls::DerivationPath,
+    pub secret_key: bls::SecretKey,
+    pub keystore: String,
+}
 
-    keystore.crypto.cipher.decrypt_secret(&decryption_key)
+/// Keystores of the signing keys of validators `start_index..start_index + count`, derived from
+/// the mnemonic at their EIP-2334 path `m/12381/3600/{index}/0/0` and encrypted with `password`,
+/// as generated by staking-deposit-cli.
+pub fn keystores_from_mnemonic(
+    mnemonic: &str,
+    passphrase: &str,
+    start_index: u32,
+    count: u32,
+    password: &str,
+    kdf_choice: KdfChoice,
+) -> Result<Vec<GeneratedKeystore>, KeystoreError> {
+    let Some(end_index) = start_index.checked_add(count) else {
+        return Err(KeystoreError::IndexOutOfRange { start_index, count });
+    };
+    let seed = mnemonic_to_seed(mnemonic, passphrase)?;
+    (start_index..end_index)
+        .map(|index| {
+            let path = bls::DerivationPath::signing_key(index);
+            let secret_key = bls::SecretKey::derive(&seed, &path)?;
+            let keystore = encrypt(
+                &secret_key.to_bytes(),
+                password,
+                kdf_choice,
+                &path.to_string(),
+                Some(""),
+            )?;
+            Ok(GeneratedKeystore {
+                path,
+                secret_key,
+                keystore,
+            })
+        })
+        .collect()
 }
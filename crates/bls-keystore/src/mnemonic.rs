@@ -0,0 +1,66 @@
+//! [BIP-39][1] mnemonics, the backup phrase of the seed validator keys are derived from.
+//!
+//![1]: https://github.com/bitcoin/bips/blob/master/bip-0039.mediawiki
+
+use crate::KeystoreError;
+use sha2::{Digest, Sha256, Sha512};
+use unicode_normalization::UnicodeNormalization;
+
+/// BIP-39 English wordlist, sorted.
+const ENGLISH: &str = include_str!("bip39_english.txt");
+const SEED_ITERATIONS: u32 = 2048;
+pub const SEED_LEN: usize = 64;
+
+/// Seed of a BIP-39 mnemonic and its optional passphrase, after checking the mnemonic's words
+/// and checksum against the English wordlist.
+pub fn mnemonic_to_seed(mnemonic: &str, passphrase: &str) -> Result<[u8; SEED_LEN], KeystoreError> {
+    let mnemonic: String = mnemonic.nfkd().collect();
+    let words: Vec<&str> = mnemonic.split_whitespace().collect();
+    validate_mnemonic(&words)?;
+
+    let salt: String = format!("mnemonic{passphrase}").nfkd().collect();
+    let mut seed = [0u8; SEED_LEN];
+    pbkdf2::pbkdf2_hmac::<Sha512>(
+        words.join(" ").as_bytes(),
+        salt.as_bytes(),
+        SEED_ITERATIONS,
+        &mut seed,
+    );
+    Ok(seed)
+}
+
+fn validate_mnemonic(words: &[&str]) -> Result<(), KeystoreError> {
+    if !matches!(words.len(), 12 | 15 | 18 | 21 | 24) {
+        return Err(KeystoreError::InvalidMnemonic(format!(
+            "must have 12, 15, 18, 21 or 24 words, not {}",
+            words.len()
+        )));
+    }
+    let wordlist: Vec<&str> = ENGLISH.lines().collect();
+    // 11 bits per word, the entropy followed by a checksum of entropy bits / 32
+    let mut bits = Vec::with_capacity(words.len() * 11);
+    for word in words {
+        let Ok(index) = wordlist.binary_search(word) else {
+            return Err(KeystoreError::InvalidMnemonic(format!(
+                "word '{}' is not in the BIP-39 English wordlist",
+                word
+            )));
+        };
+        bits.extend((0..11).rev().map(|bit| (index >> bit) & 1 == 1));
+    }
+
+    let checksum_len = bits.len() / 33;
+    let (entropy_bits, checksum) = bits.split_at(bits.len() - checksum_len);
+    let entropy: Vec<u8> = entropy_bits
+        .chunks(8)
+        .map(|byte| byte.iter().fold(0u8, |acc, bit| acc << 1 | *bit as u8))
+        .collect();
+    let hash = Sha256::digest(&entropy);
+    let expected = (0..checksum_len).map(|i| (hash[i / 8] >> (7 - i % 8)) & 1 == 1);
+    if !expected.eq(checksum.iter().copied()) {
+        return Err(KeystoreError::InvalidMnemonic(
+            "checksum mismatch".to_string(),
+        ));
+    }
+    Ok(())
+}
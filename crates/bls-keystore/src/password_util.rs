@@ -18,13 +18,16 @@ pub fn validate_decryption_key(
     cipher_message: &[u8],
     checksum_message: &[u8],
 ) -> bool {
+    checksum(decryption_key, cipher_message) == checksum_message
+}
+
+/// sha256 of the second 16 bytes of the decryption key and the cipher message
+pub fn checksum(decryption_key: &[u8], cipher_message: &[u8]) -> Vec<u8> {
     let dk_slice = &decryption_key[16..32];
     let pre_image = [dk_slice, cipher_message].concat();
     let mut hasher = Sha256::new();
     hasher.update(pre_image);
-    let checksum = hasher.finalize();
-
-    checksum.eq(checksum_message.into())
+    hasher.finalize().to_vec()
 }
 
 fn is_c0(c: &char) -> bool {
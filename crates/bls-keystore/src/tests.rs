@@ -81,7 +81,72 @@ fn decrypt_secret() {
 #[test]
 fn invalid_keystore() {
     let decrypted_result = decrypt("{}", PASSWORD);
-    assert!(&decrypted_result.is_err());
+    assert!(matches!(
+        decrypted_result,
+        Err(KeystoreError::InvalidJson(_))
+    ));
+}
+
+/// SCRYPT_TEST_VECTOR with `pointer` replaced by `value`.
+fn modified_keystore(pointer: &str, value: serde_json::Value) -> String {
+    let mut keystore: serde_json::Value = serde_json::from_str(SCRYPT_TEST_VECTOR).unwrap();
+    *keystore.pointer_mut(pointer).unwrap() = value;
+    keystore.to_string()
+}
+
+#[test]
+fn decrypt_errors_are_typed() {
+    use serde_json::json;
+
+    let decrypt_modified =
+        |pointer, value| decrypt(&modified_keystore(pointer, value), PASSWORD).unwrap_err();
+
+    assert!(matches!(
+        decrypt_modified("/version", json!(3)),
+        KeystoreError::UnsupportedVersion(3)
+    ));
+    assert!(matches!(
+        decrypt_modified("/crypto/kdf/function", json!("argon2id")),
+        KeystoreError::UnsupportedKdf(function) if function == "argon2id"
+    ));
+    assert!(matches!(
+        decrypt_modified("/crypto/cipher/function", json!("aes-256-gcm")),
+        KeystoreError::UnsupportedCipher(function) if function == "aes-256-gcm"
+    ));
+    assert!(matches!(
+        decrypt_modified("/crypto/checksum/function", json!("sha512")),
+        KeystoreError::UnsupportedChecksum(function) if function == "sha512"
+    ));
+    assert!(matches!(
+        decrypt_modified("/crypto/kdf/params/n", json!(500)),
+        KeystoreError::InvalidKdfParams(_)
+    ));
+    assert!(matches!(
+        decrypt_modified("/crypto/kdf/params/dklen", json!(16)),
+        KeystoreError::InvalidKdfParams(_)
+    ));
+    assert!(matches!(
+        decrypt_modified("/crypto/cipher/params/iv", json!("264daa3f303d7259")),
+        KeystoreError::InvalidIvLength(8)
+    ));
+    assert!(matches!(
+        decrypt_modified("/crypto/kdf/params/salt", json!("00")),
+        KeystoreError::WrongPassword
+    ));
+    let other_pubkey = "a99a76ed7796f7be22d5b7e85deeb7c5677e88e511e0b337618f8c4eb61349b4bf2d153f649f7b53359fe8b94a38e44c";
+    assert!(matches!(
+        decrypt_modified("/pubkey", json!(other_pubkey)),
+        KeystoreError::PubkeyMismatch(pubkey) if pubkey == other_pubkey
+    ));
+
+    let secret = hex::decode(SECRET).unwrap();
+    let mut keystore: serde_json::Value =
+        serde_json::from_str(&encrypt(&secret, PASSWORD, TEST_PBKDF2, "", None).unwrap()).unwrap();
+    keystore["crypto"]["kdf"]["params"]["prf"] = json!("hmac-md5");
+    assert!(matches!(
+        decrypt(&keystore.to_string(), PASSWORD),
+        Err(KeystoreError::UnsupportedPrf(prf)) if prf == "hmac-md5"
+    ));
 }
 
 #[test]
@@ -90,3 +155,241 @@ fn decrypt_keystore_with_invalid_password() {
     let err = decrypted_result.err().unwrap();
     assert_eq!(err.to_string(), "Password verification failed");
 }
+
+const TEST_SCRYPT: KdfChoice = KdfChoice::Scrypt { n: 512, r: 8, p: 1 };
+const TEST_PBKDF2: KdfChoice = KdfChoice::Pbkdf2 { c: 512 };
+
+#[test]
+fn encrypted_keystore_round_trips() {
+    let secret = hex::decode(SECRET).unwrap();
+
+    for kdf_choice in [TEST_SCRYPT, TEST_PBKDF2] {
+        let keystore_json = encrypt(
+            &secret,
+            PASSWORD,
+            kdf_choice,
+            "m/12381/3600/0/0/0",
+            Some("test keystore"),
+        )
+        .unwrap();
+        assert_eq!(decrypt(&keystore_json, PASSWORD).unwrap(), secret);
+
+        let keystore = keystore::parse_keystore(&keystore_json).unwrap();
+        assert_eq!(keystore.pubkey, Some("9612d7a727c9d0a22e185a1c768478dfe919cada9266988cb32359c11f2b7b27f4ae4040902382ae2910c15e2b420d07".to_string()));
+        assert_eq!(keystore.path, "m/12381/3600/0/0/0");
+        assert_eq!(keystore.description, Some("test keystore".to_string()));
+        assert_eq!(keystore.crypto.cipher.function, "aes-128-ctr");
+        assert_eq!(keystore.crypto.cipher.params.iv.len(), 16);
+        assert_eq!(keystore.crypto.checksum.function, "sha256");
+    }
+}
+
+#[test]
+fn encrypt_uses_fresh_salt_iv_and_uuid() {
+    let secret = hex::decode(SECRET).unwrap();
+    let first =
+        keystore::parse_keystore(&encrypt(&secret, PASSWORD, TEST_PBKDF2, "", None).unwrap())
+            .unwrap();
+    let second =
+        keystore::parse_keystore(&encrypt(&secret, PASSWORD, TEST_PBKDF2, "", None).unwrap())
+            .unwrap();
+
+    assert_ne!(first.crypto.kdf, second.crypto.kdf);
+    assert_ne!(
+        first.crypto.cipher.params.iv,
+        second.crypto.cipher.params.iv
+    );
+    assert_ne!(first.uuid, second.uuid);
+    assert!(first.description.is_none());
+}
+
+#[test]
+fn encrypted_keystore_rejects_wrong_password() {
+    let secret = hex::decode(SECRET).unwrap();
+    let keystore_json = encrypt(&secret, PASSWORD, TEST_SCRYPT, "", None).unwrap();
+    let err = decrypt(&keystore_json, "test").err().unwrap();
+    assert_eq!(err.to_string(), "Password verification failed");
+}
+
+#[test]
+fn encrypt_rejects_invalid_input() {
+    let secret = hex::decode(SECRET).unwrap();
+    let invalid_scrypt = KdfChoice::Scrypt { n: 500, r: 8, p: 1 };
+    assert!(encrypt(&secret, PASSWORD, invalid_scrypt, "", None).is_err());
+    assert!(encrypt(&[0u8; 32], PASSWORD, TEST_SCRYPT, "", None).is_err());
+}
+
+#[test]
+fn change_password_preserves_keystore_metadata() {
+    let secret = hex::decode(SECRET).unwrap();
+    let original = keystore::parse_keystore(SCRYPT_TEST_VECTOR).unwrap();
+
+    let keystore_json = change_password(
+        SCRYPT_TEST_VECTOR,
+        PASSWORD,
+        "new password",
+        Some(TEST_PBKDF2),
+    )
+    .unwrap();
+
+    assert_eq!(decrypt(&keystore_json, "new password").unwrap(), secret);
+    assert!(decrypt(&keystore_json, PASSWORD).is_err());
+    let keystore = keystore::parse_keystore(&keystore_json).unwrap();
+    assert_eq!(keystore.pubkey, original.pubkey);
+    assert_eq!(keystore.path, original.path);
+    assert_eq!(keystore.description, original.description);
+    assert_eq!(keystore.uuid, original.uuid);
+    assert_eq!(
+        keystore.crypto.kdf.kdf_choice().unwrap(),
+        KdfChoice::Pbkdf2 { c: 512 }
+    );
+
+    // the KDF and its cost parameters are kept when none is chosen
+    let keystore_json = change_password(&keystore_json, "new password", PASSWORD, None).unwrap();
+    assert_eq!(decrypt(&keystore_json, PASSWORD).unwrap(), secret);
+    let keystore = keystore::parse_keystore(&keystore_json).unwrap();
+    assert_eq!(keystore.crypto.kdf.kdf_choice().unwrap(), TEST_PBKDF2);
+    assert_eq!(keystore.uuid, original.uuid);
+}
+
+#[test]
+fn change_password_rejects_wrong_password_and_pubkey() {
+    let err = change_password(SCRYPT_TEST_VECTOR, "test", "new password", None)
+        .err()
+        .unwrap();
+    assert_eq!(err.to_string(), "Password verification failed");
+
+    let other_pubkey = bls::SecretKey::derive(&[7u8; 32], &bls::DerivationPath::signing_key(0))
+        .unwrap()
+        .public_key();
+    let keystore_json = SCRYPT_TEST_VECTOR.replace(
+        "9612d7a727c9d0a22e185a1c768478dfe919cada9266988cb32359c11f2b7b27f4ae4040902382ae2910c15e2b420d07",
+        &hex::encode(other_pubkey.compress()),
+    );
+    let err = change_password(&keystore_json, PASSWORD, "new password", None)
+        .err()
+        .unwrap();
+    assert!(err.to_string().ends_with("does not match its secret key"));
+}
+
+#[test]
+fn path_is_checked_against_seed() {
+    let seed = [7u8; 32];
+    let path = bls::DerivationPath::signing_key(1);
+    let secret = bls::SecretKey::derive(&seed, &path).unwrap().to_bytes();
+    let keystore = encrypt(
+        &secret,
+        "password",
+        KdfChoice::Pbkdf2 { c: 2 },
+        &path.to_string(),
+        None,
+    )
+    .unwrap();
+
+    assert!(path_matches_seed(&keystore, &seed).unwrap());
+    assert!(!path_matches_seed(&keystore, &[8u8; 32]).unwrap());
+
+    let other_path = keystore.replace("m/12381/3600/1/0/0", "m/12381/3600/2/0/0");
+    assert!(!path_matches_seed(&other_path, &seed).unwrap());
+
+    let invalid_path = keystore.replace("m/12381/3600/1/0/0", "m/12381/3600/1/0/0'");
+    assert!(path_matches_seed(&invalid_path, &seed).is_err());
+    assert!(path_matches_seed(&keystore, &seed[..16]).is_err());
+    // the EIP-2335 test vectors aren't derived from a seed
+    assert!(path_matches_seed(SCRYPT_TEST_VECTOR, &seed).is_ok());
+}
+
+// BIP-39 test vectors of the reference implementation, with the passphrase "TREZOR"
+const BIP39_TEST_VECTORS: [(&str, &str); 5] = [
+    (
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+    ),
+    (
+        "legal winner thank year wave sausage worth useful legal winner thank yellow",
+        "2e8905819b8723fe2c1d161860e5ee1830318dbf49a83bd451cfb8440c28bd6fa457fe1296106559a3c80937a1c1069be3a3a5bd381ee6260e8d9739fce1f607",
+    ),
+    (
+        "letter advice cage absurd amount doctor acoustic avoid letter advice cage above",
+        "d71de856f81a8acc65e6fc851a38d4d7ec216fd0796d0a6827a3ad6ed5511a30fa280f12eb2e47ed2ac03b5c462a0358d18d69fe4f985ec81778c1b370b652a8",
+    ),
+    (
+        "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo wrong",
+        "ac27495480225222079d7be181583751e86f571027b0497b5b5d11218e0a8a13332572917f0f8e5a589620c6f15b11c61dee327651a14c34e18231052e48c069",
+    ),
+    (
+        "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo vote",
+        "dd48c104698c30cfe2b6142103248622fb7bb0ff692eebb00089b32d22484e1613912f0a5b694407be899ffd31ed3992c456cdf60f5d4564b8ba3f05a69890ad",
+    ),
+];
+
+#[test]
+fn mnemonic_to_seed_matches_bip39_test_vectors() {
+    for (mnemonic, seed) in BIP39_TEST_VECTORS {
+        assert_eq!(
+            hex::encode(mnemonic_to_seed(mnemonic, "TREZOR").unwrap()),
+            seed
+        );
+    }
+    // surrounding and repeated whitespace is ignored
+    let padded = format!("  {}\n", BIP39_TEST_VECTORS[1].0.replace(' ', "   "));
+    assert_eq!(
+        hex::encode(mnemonic_to_seed(&padded, "TREZOR").unwrap()),
+        BIP39_TEST_VECTORS[1].1
+    );
+}
+
+#[test]
+fn invalid_mnemonics_are_rejected() {
+    for (mnemonic, error) in [
+        ("abandon abandon about", "must have 12, 15, 18, 21 or 24 words"),
+        (
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandno",
+            "'abandno' is not in the BIP-39 English wordlist",
+        ),
+        (
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon",
+            "Invalid mnemonic: checksum mismatch",
+        ),
+    ] {
+        let message = mnemonic_to_seed(mnemonic, "").unwrap_err().to_string();
+        assert!(message.contains(error), "{message}");
+    }
+}
+
+#[test]
+fn keystores_are_generated_from_mnemonic() {
+    let mnemonic = BIP39_TEST_VECTORS[0].0;
+    let keystores = keystores_from_mnemonic(
+        mnemonic,
+        "TREZOR",
+        3,
+        2,
+        "password",
+        KdfChoice::Pbkdf2 { c: 2 },
+    )
+    .unwrap();
+
+    let seed = mnemonic_to_seed(mnemonic, "TREZOR").unwrap();
+    assert_eq!(keystores.len(), 2);
+    for (generated, index) in keystores.iter().zip(3..) {
+        let path = generated.path.to_string();
+        assert_eq!(path, format!("m/12381/3600/{index}/0/0"));
+        let secret = decrypt(&generated.keystore, "password").unwrap();
+        assert_eq!(
+            secret,
+            bls::SecretKey::derive(&seed, &generated.path)
+                .unwrap()
+                .to_bytes()
+        );
+        assert_eq!(secret, generated.secret_key.to_bytes());
+        let json: serde_json::Value = serde_json::from_str(&generated.keystore).unwrap();
+        assert_eq!(json["path"], path);
+        assert_eq!(json["description"], "");
+        assert!(path_matches_seed(&generated.keystore, &seed).unwrap());
+    }
+
+    assert!(
+        keystores_from_mnemonic(mnemonic, "", u32::MAX, 2, "password", KdfChoice::PBKDF2).is_err()
+    );
+}
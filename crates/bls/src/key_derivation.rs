@@ -0,0 +1,185 @@
+//! Hierarchical deterministic derivation of BLS12-381 secret keys from a seed, as defined by
+//! [EIP-2333][1], and the derivation paths of validator keys defined by [EIP-2334][2].
+//!
+//![1]: https://eips.ethereum.org/EIPS/eip-2333
+//![2]: https://eips.ethereum.org/EIPS/eip-2334
+
+use crate::{BlsError, SecretKey, SECRET_KEY_BYTES_LEN};
+use hmac::{Hmac, Mac};
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::str::FromStr;
+
+/// Minimum length of the seed of `derive_master_SK`.
+pub const MIN_SEED_LEN: usize = 32;
+
+/// EIP-2334 `purpose` level of BLS12-381 keys.
+pub const PURPOSE: u32 = 12381;
+/// EIP-2334 `coin_type` level of Ethereum.
+pub const COIN_TYPE: u32 = 3600;
+
+const KEYGEN_SALT: &[u8] = b"BLS-SIG-KEYGEN-SALT-";
+/// Length of the HKDF output reduced modulo r, `ceil((3 * ceil(log2(r))) / 16)`.
+const HKDF_MOD_R_L: usize = 48;
+/// Number of 32 bytes chunks of a lamport secret key.
+const LAMPORT_CHUNKS: usize = 255;
+/// Order of the BLS12-381 G1 and G2 subgroups.
+const CURVE_ORDER: [u8; 32] = [
+    0x73, 0xed, 0xa7, 0x53, 0x29, 0x9d, 0x7d, 0x48, 0x33, 0x39, 0xd8, 0x08, 0x09, 0xa1, 0xd8, 0x05,
+    0x53, 0xbd, 0xa4, 0x02, 0xff, 0xfe, 0x5b, 0xfe, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x01,
+];
+
+impl SecretKey {
+    /// `derive_master_SK` of EIP-2333, the root of the key tree of `seed`.
+    pub fn derive_master(seed: &[u8]) -> Result<Self, BlsError> {
+        if seed.len() < MIN_SEED_LEN {
+            return Err(BlsError::InvalidSeed);
+        }
+        SecretKey::from_bytes(&hkdf_mod_r(seed))
+    }
+
+    /// `derive_child_SK` of EIP-2333, the child key at `index` of this key.
+    pub fn derive_child(&self, index: u32) -> Self {
+        let compressed_lamport_pk = parent_sk_to_lamport_pk(&self.to_bytes(), index);
+        SecretKey::from_bytes(&hkdf_mod_r(&compressed_lamport_pk))
+            .expect("HKDF_mod_r is a non zero scalar below r")
+    }
+
+    /// The key at `path` of the key tree of `seed`.
+    pub fn derive(seed: &[u8], path: &DerivationPath) -> Result<Self, BlsError> {
+        Ok(path
+            .indices()
+            .iter()
+            .fold(SecretKey::derive_master(seed)?, |parent, index| {
+                parent.derive_child(*index)
+            }))
+    }
+}
+
+/// EIP-2333 path such as `m/12381/3600/0/0/0`, the child indices from the master key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivationPath(Vec<u32>);
+
+impl DerivationPath {
+    /// EIP-2334 path of the signing key of validator `index`, `m/12381/3600/{index}/0/0`.
+    pub fn signing_key(index: u32) -> Self {
+        DerivationPath(vec![PURPOSE, COIN_TYPE, index, 0, 0])
+    }
+
+    /// EIP-2334 path of the withdrawal key of validator `index`, `m/12381/3600/{index}/0`.
+    pub fn withdrawal_key(index: u32) -> Self {
+        DerivationPath(vec![PURPOSE, COIN_TYPE, index, 0])
+    }
+
+    pub fn indices(&self) -> &[u32] {
+        &self.0
+    }
+
+    /// Check the path is an EIP-2334 `m / purpose / coin_type / account / use` path of
+    /// Ethereum, optionally followed by further levels such as the signing key level.
+    pub fn validate_eip2334(&self) -> Result<(), BlsError> {
+        match self.0.as_slice() {
+            [PURPOSE, COIN_TYPE, _account, _use, ..] => Ok(()),
+            [PURPOSE, coin_type, ..] if *coin_type != COIN_TYPE => Err(BlsError::InvalidPath(
+                format!("{self}: coin_type must be {COIN_TYPE}"),
+            )),
+            [purpose, ..] if *purpose != PURPOSE => Err(BlsError::InvalidPath(format!(
+                "{self}: purpose must be {PURPOSE}"
+            ))),
+            _ => Err(BlsError::InvalidPath(format!(
+                "{self}: expected m/{PURPOSE}/{COIN_TYPE}/account/use"
+            ))),
+        }
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = BlsError;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        let mut levels = path.trim().split('/');
+        if levels.next() != Some("m") {
+            return Err(BlsError::InvalidPath(format!("{path}: must start with m")));
+        }
+        levels
+            .map(|level| {
+                // u32::from_str accepts a leading `+`, EIP-2334 levels are plain digits
+                if level.is_empty() || !level.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(BlsError::InvalidPath(format!(
+                        "{path}: invalid level '{level}'"
+                    )));
+                }
+                level.parse().map_err(|_| {
+                    BlsError::InvalidPath(format!("{path}: level {level} is not below 2^32"))
+                })
+            })
+            .collect::<Result<_, _>>()
+            .map(DerivationPath)
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "m")?;
+        self.0.iter().try_for_each(|index| write!(f, "/{index}"))
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[&[u8]]) -> [u8; 32] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any length");
+    data.iter().for_each(|d| mac.update(d));
+    mac.finalize().into_bytes().into()
+}
+
+/// HKDF-Extract followed by HKDF-Expand of RFC 5869 with SHA-256.
+fn hkdf(salt: &[u8], ikm: &[&[u8]], info: &[u8], okm: &mut [u8]) {
+    let prk = hmac_sha256(salt, ikm);
+    let mut t = [0u8; 32];
+    for (i, chunk) in okm.chunks_mut(32).enumerate() {
+        let previous: &[u8] = if i == 0 { &[] } else { &t };
+        t = hmac_sha256(&prk, &[previous, info, &[(i + 1) as u8]]);
+        chunk.copy_from_slice(&t[..chunk.len()]);
+    }
+}
+
+fn hkdf_mod_r(ikm: &[u8]) -> [u8; SECRET_KEY_BYTES_LEN] {
+    let r = BigUint::from_bytes_be(&CURVE_ORDER);
+    let mut salt: [u8; 32] = Sha256::digest(KEYGEN_SALT).into();
+    loop {
+        let mut okm = [0u8; HKDF_MOD_R_L];
+        hkdf(
+            &salt,
+            &[ikm, &[0]],
+            &(HKDF_MOD_R_L as u16).to_be_bytes(),
+            &mut okm,
+        );
+        let sk = BigUint::from_bytes_be(&okm) % &r;
+        if sk != BigUint::default() {
+            let sk = sk.to_bytes_be();
+            let mut bytes = [0u8; SECRET_KEY_BYTES_LEN];
+            bytes[SECRET_KEY_BYTES_LEN - sk.len()..].copy_from_slice(&sk);
+            return bytes;
+        }
+        salt = Sha256::digest(salt).into();
+    }
+}
+
+fn ikm_to_lamport_sk(ikm: &[u8], salt: &[u8]) -> Vec<u8> {
+    let mut okm = vec![0u8; 32 * LAMPORT_CHUNKS];
+    hkdf(salt, &[ikm], &[], &mut okm);
+    okm
+}
+
+fn parent_sk_to_lamport_pk(parent_sk: &[u8; SECRET_KEY_BYTES_LEN], index: u32) -> [u8; 32] {
+    let salt = index.to_be_bytes();
+    let not_ikm = parent_sk.map(|b| !b);
+    let lamport_0 = ikm_to_lamport_sk(parent_sk, &salt);
+    let lamport_1 = ikm_to_lamport_sk(&not_ikm, &salt);
+    let mut lamport_pk = Sha256::new();
+    lamport_0
+        .chunks(32)
+        .chain(lamport_1.chunks(32))
+        .for_each(|chunk| lamport_pk.update(Sha256::digest(chunk)));
+    lamport_pk.finalize().into()
+}
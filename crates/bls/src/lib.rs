@@ -0,0 +1,164 @@
+//! BLS12-381 signatures as used by the consensus layer, backed by [blst][1].
+//! Public keys are in G1 and signatures in G2, using the proof of possession ciphersuite
+//! `BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_`.
+//!
+//![1]: https://github.com/supranational/blst
+
+mod key_derivation;
+
+#[cfg(test)]
+mod tests;
+
+use blst::min_pk;
+use blst::BLST_ERROR;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use thiserror::Error;
+
+pub use key_derivation::{DerivationPath, COIN_TYPE, MIN_SEED_LEN, PURPOSE};
+
+pub type Hash256 = primitive_types::H256;
+
+/// Domain separation tag of the eth2 BLS ciphersuite.
+pub const DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+pub const SECRET_KEY_BYTES_LEN: usize = 32;
+pub const PUBLIC_KEY_BYTES_LEN: usize = 48;
+pub const SIGNATURE_BYTES_LEN: usize = 96;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum BlsError {
+    #[error("Invalid secret key")]
+    InvalidSecretKey,
+    #[error("Invalid public key")]
+    InvalidPublicKey,
+    #[error("Invalid signature")]
+    InvalidSignature,
+    #[error("Seed must be at least {MIN_SEED_LEN} bytes")]
+    InvalidSeed,
+    #[error("Invalid derivation path {0}")]
+    InvalidPath(String),
+}
+
+#[derive(Clone)]
+pub struct SecretKey(min_pk::SecretKey);
+
+impl SecretKey {
+    /// Create secret key from its big endian 32 bytes representation, such as the output of
+    /// `bls_keystore::decrypt`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BlsError> {
+        min_pk::SecretKey::from_bytes(bytes)
+            .map(SecretKey)
+            .map_err(|_| BlsError::InvalidSecretKey)
+    }
+
+    pub fn to_bytes(&self) -> [u8; SECRET_KEY_BYTES_LEN] {
+        self.0.to_bytes()
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey(self.0.sk_to_pk())
+    }
+
+    /// Sign the signing root with the eth2 ciphersuite.
+    pub fn sign(&self, signing_root: &Hash256) -> Signature {
+        Signature(self.0.sign(signing_root.as_bytes(), DST, &[]))
+    }
+}
+
+/// Never expose secret key material in logs.
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretKey({})", self.public_key())
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct PublicKey(min_pk::PublicKey);
+
+impl PublicKey {
+    /// Deserialize and validate a compressed (or uncompressed) public key.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BlsError> {
+        min_pk::PublicKey::key_validate(bytes)
+            .map(PublicKey)
+            .map_err(|_| BlsError::InvalidPublicKey)
+    }
+
+    /// Deserialize a `0x` prefixed hex encoded public key.
+    pub fn from_hex(hex_str: &str) -> Result<Self, BlsError> {
+        let bytes = hex::decode(hex_str.strip_prefix("0x").unwrap_or(hex_str))
+            .map_err(|_| BlsError::InvalidPublicKey)?;
+        PublicKey::from_bytes(&bytes)
+    }
+
+    pub fn compress(&self) -> [u8; PUBLIC_KEY_BYTES_LEN] {
+        self.0.compress()
+    }
+}
+
+/// `0x` prefixed hex of the compressed public key.
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{}", hex::encode(self.compress()))
+    }
+}
+
+impl fmt::Debug for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PublicKey({})", self)
+    }
+}
+
+impl Serialize for PublicKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for PublicKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex_str = String::deserialize(deserializer)?;
+        PublicKey::from_hex(&hex_str).map_err(de::Error::custom)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Signature(min_pk::Signature);
+
+impl Signature {
+    /// Deserialize and group check a compressed (or uncompressed) signature.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BlsError> {
+        min_pk::Signature::sig_validate(bytes, true)
+            .map(Signature)
+            .map_err(|_| BlsError::InvalidSignature)
+    }
+
+    pub fn compress(&self) -> [u8; SIGNATURE_BYTES_LEN] {
+        self.0.compress()
+    }
+
+    /// Verify the signature of the signing root against the public key.
+    pub fn verify(&self, public_key: &PublicKey, signing_root: &Hash256) -> bool {
+        self.0.verify(
+            false,
+            signing_root.as_bytes(),
+            DST,
+            &[],
+            &public_key.0,
+            false,
+        ) == BLST_ERROR::BLST_SUCCESS
+    }
+}
+
+/// `0x` prefixed hex of the compressed signature.
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{}", hex::encode(self.compress()))
+    }
+}
+
+impl fmt::Debug for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Signature({})", self)
+    }
+}
@@ -0,0 +1,271 @@
+use super::*;
+use hex_literal::hex;
+
+// EIP-2335 test vector secret and its public key
+const EIP2335_SECRET: [u8; 32] =
+    hex!("000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f");
+const EIP2335_PUBKEY: [u8; 48] = hex!("9612d7a727c9d0a22e185a1c768478dfe919cada9266988cb32359c11f2b7b27f4ae4040902382ae2910c15e2b420d07");
+
+struct SignTestVector {
+    privkey: [u8; 32],
+    pubkey: [u8; 48],
+    message: [u8; 32],
+    signature: [u8; 96],
+}
+
+// consensus-spec-tests bls/sign and bls/verify vectors
+const SIGN_TEST_VECTORS: [SignTestVector; 3] = [
+    SignTestVector {
+        privkey: hex!("263dbd792f5b1be47ed85f8938c0f29586af0d3ac7b977f21c278fe1462040e3"),
+        pubkey: hex!("a491d1b0ecd9bb917989f0e74f0dea0422eac4a873e5e2644f368dffb9a6e20fd6e10c1b77654d067c0618f6e5a7f79a"),
+        message: [0; 32],
+        signature: hex!("b6ed936746e01f8ecf281f020953fbf1f01debd5657c4a383940b020b26507f6076334f91e2366c96e9ab279fb5158090352ea1c5b0c9274504f4f0e7053af24802e51e4568d164fe986834f41e55c8e850ce1f98458c0cfc9ab380b55285a55"),
+    },
+    SignTestVector {
+        privkey: hex!("47b8192d77bf871b62e87859d653922725724a5c031afeabc60bcef5ff665138"),
+        pubkey: hex!("b301803f8b5ac4a1133581fc676dfedc60d891dd5fa99028805e5ea5b08d3491af75d0707adab3b70c6a6a580217bf81"),
+        message: [0; 32],
+        signature: hex!("b23c46be3a001c63ca711f87a005c200cc550b9429d5f4eb38d74322144f1b63926da3388979e5321012fb1a0526bcd100b5ef5fe72628ce4cd5e904aeaa3279527843fae5ca9ca675f4f51ed8f83bbf7155da9ecc9663100a885d5dc6df96d9"),
+    },
+    SignTestVector {
+        privkey: hex!("328388aff0d4a5b7dc9205abd374e7e98f3cd9f3418edb4eafda5fb16473d216"),
+        pubkey: hex!("b53d21a4cfd562c469cc81514d4ce5a6b577d8403d32a394dc265dd190b47fa9f829fdd7963afdf972e5e77854051f6f"),
+        message: [0; 32],
+        signature: hex!("948a7cb99f76d616c2c564ce9bf4a519f1bea6b0a624a02276443c245854219fabb8d4ce061d255af5330b078d5380681751aa7053da2c98bae898edc218c75f07e24d8802a17cd1f6833b71e58f5eb5b94208b4d0bb3848cecb075ea21be115"),
+    },
+];
+
+#[test]
+fn public_key_is_derived_from_eip2335_secret() {
+    let secret_key = SecretKey::from_bytes(&EIP2335_SECRET).unwrap();
+    assert_eq!(secret_key.public_key().compress(), EIP2335_PUBKEY);
+    assert_eq!(
+        secret_key.public_key().to_string(),
+        format!("0x{}", hex::encode(EIP2335_PUBKEY))
+    );
+}
+
+#[test]
+fn sign_matches_test_vectors() {
+    for vector in SIGN_TEST_VECTORS {
+        let secret_key = SecretKey::from_bytes(&vector.privkey).unwrap();
+        assert_eq!(secret_key.public_key().compress(), vector.pubkey);
+
+        let signature = secret_key.sign(&Hash256::from(vector.message));
+        assert_eq!(signature.compress(), vector.signature);
+    }
+}
+
+#[test]
+fn verify_matches_test_vectors() {
+    for vector in SIGN_TEST_VECTORS {
+        let public_key = PublicKey::from_bytes(&vector.pubkey).unwrap();
+        let signature = Signature::from_bytes(&vector.signature).unwrap();
+        assert!(signature.verify(&public_key, &Hash256::from(vector.message)));
+        assert!(!signature.verify(&public_key, &Hash256::repeat_byte(0x56)));
+    }
+}
+
+#[test]
+fn verify_fails_with_wrong_public_key() {
+    let signature = Signature::from_bytes(&SIGN_TEST_VECTORS[0].signature).unwrap();
+    let public_key = PublicKey::from_bytes(&SIGN_TEST_VECTORS[1].pubkey).unwrap();
+    assert!(!signature.verify(&public_key, &Hash256::zero()));
+}
+
+#[test]
+fn secret_key_round_trips() {
+    let secret_key = SecretKey::from_bytes(&EIP2335_SECRET).unwrap();
+    assert_eq!(secret_key.to_bytes(), EIP2335_SECRET);
+}
+
+#[test]
+fn invalid_keys_are_rejected() {
+    assert_eq!(
+        SecretKey::from_bytes(&[0u8; 32]).err(),
+        Some(BlsError::InvalidSecretKey)
+    );
+    assert_eq!(
+        SecretKey::from_bytes(&[0xffu8; 32]).err(),
+        Some(BlsError::InvalidSecretKey)
+    );
+    assert_eq!(
+        PublicKey::from_bytes(&[0u8; 48]).err(),
+        Some(BlsError::InvalidPublicKey)
+    );
+    assert_eq!(
+        Signature::from_bytes(&[0u8; 96]).err(),
+        Some(BlsError::InvalidSignature)
+    );
+}
+
+#[test]
+fn public_key_from_hex() {
+    let public_key = PublicKey::from_hex("0x9612d7a727c9d0a22e185a1c768478dfe919cada9266988cb32359c11f2b7b27f4ae4040902382ae2910c15e2b420d07").unwrap();
+    assert_eq!(public_key.compress(), EIP2335_PUBKEY);
+    assert!(PublicKey::from_hex("0x1234").is_err());
+}
+
+#[test]
+fn public_key_serde_round_trips() {
+    let json = r#""0x9612d7a727c9d0a22e185a1c768478dfe919cada9266988cb32359c11f2b7b27f4ae4040902382ae2910c15e2b420d07""#;
+    let public_key: PublicKey = serde_json::from_str(json).unwrap();
+    assert_eq!(public_key.compress(), EIP2335_PUBKEY);
+    assert_eq!(serde_json::to_string(&public_key).unwrap(), json);
+    assert!(serde_json::from_str::<PublicKey>(r#""0x1234""#).is_err());
+}
+
+struct Eip2333TestVector {
+    seed: &'static str,
+    master_sk: &'static str,
+    child_index: u32,
+    child_sk: &'static str,
+}
+
+// EIP-2333 test cases, secret keys in decimal
+const EIP2333_TEST_VECTORS: [Eip2333TestVector; 4] = [
+    Eip2333TestVector {
+        seed: "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+        master_sk: "6083874454709270928345386274498605044986640685124978867557563392430687146096",
+        child_index: 0,
+        child_sk: "20397789859736650942317412262472558107875392172444076792671091975210932703118",
+    },
+    Eip2333TestVector {
+        seed: "3141592653589793238462643383279502884197169399375105820974944592",
+        master_sk: "29757020647961307431480504535336562678282505419141012933316116377660817309383",
+        child_index: 3141592653,
+        child_sk: "25457201688850691947727629385191704516744796114925897962676248250929345014287",
+    },
+    Eip2333TestVector {
+        seed: "0099FF991111002299DD7744EE3355BBDD8844115566CC55663355668888CC00",
+        master_sk: "27580842291869792442942448775674722299803720648445448686099262467207037398656",
+        child_index: 4294967295,
+        child_sk: "29358610794459428860402234341874281240803786294062035874021252734817515685787",
+    },
+    Eip2333TestVector {
+        seed: "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3",
+        master_sk: "19022158461524446591288038168518313374041767046816487870552872741050760015818",
+        child_index: 42,
+        child_sk: "31372231650479070279774297061823572166496564838472787488249775572789064611981",
+    },
+];
+
+fn decimal_secret_key(decimal: &str) -> [u8; 32] {
+    let bytes = num_bigint::BigUint::parse_bytes(decimal.as_bytes(), 10)
+        .unwrap()
+        .to_bytes_be();
+    let mut secret = [0u8; 32];
+    secret[32 - bytes.len()..].copy_from_slice(&bytes);
+    secret
+}
+
+#[test]
+fn key_derivation_matches_eip2333_test_vectors() {
+    for vector in EIP2333_TEST_VECTORS {
+        let seed = hex::decode(vector.seed).unwrap();
+        let master_sk = SecretKey::derive_master(&seed).unwrap();
+        assert_eq!(master_sk.to_bytes(), decimal_secret_key(vector.master_sk));
+
+        let child_sk = master_sk.derive_child(vector.child_index);
+        assert_eq!(child_sk.to_bytes(), decimal_secret_key(vector.child_sk));
+
+        // blst implements EIP-2333 as well
+        let blst_master_sk = min_pk::SecretKey::derive_master_eip2333(&seed).unwrap();
+        assert_eq!(master_sk.to_bytes(), blst_master_sk.to_bytes());
+        assert_eq!(
+            child_sk.to_bytes(),
+            blst_master_sk
+                .derive_child_eip2333(vector.child_index)
+                .to_bytes()
+        );
+    }
+}
+
+#[test]
+fn short_seed_is_rejected() {
+    assert_eq!(
+        SecretKey::derive_master(&[1u8; 31]).err(),
+        Some(BlsError::InvalidSeed)
+    );
+}
+
+#[test]
+fn key_is_derived_along_path() {
+    let seed = hex::decode(EIP2333_TEST_VECTORS[0].seed).unwrap();
+    let path = DerivationPath::signing_key(3);
+    let expected = path
+        .indices()
+        .iter()
+        .fold(
+            min_pk::SecretKey::derive_master_eip2333(&seed).unwrap(),
+            |parent, index| parent.derive_child_eip2333(*index),
+        )
+        .to_bytes();
+    assert_eq!(
+        SecretKey::derive(&seed, &path).unwrap().to_bytes(),
+        expected
+    );
+
+    let master_path: DerivationPath = "m".parse().unwrap();
+    assert_eq!(
+        SecretKey::derive(&seed, &master_path).unwrap().to_bytes(),
+        decimal_secret_key(EIP2333_TEST_VECTORS[0].master_sk)
+    );
+}
+
+#[test]
+fn derivation_path_round_trips() {
+    let path: DerivationPath = "m/12381/3600/7/0/0".parse().unwrap();
+    assert_eq!(path, DerivationPath::signing_key(7));
+    assert_eq!(path.indices(), &[12381, 3600, 7, 0, 0]);
+    assert_eq!(path.to_string(), "m/12381/3600/7/0/0");
+    assert_eq!(
+        DerivationPath::withdrawal_key(7).to_string(),
+        "m/12381/3600/7/0"
+    );
+    assert_eq!(
+        "m/4294967295".parse::<DerivationPath>().unwrap().indices(),
+        &[u32::MAX]
+    );
+}
+
+#[test]
+fn invalid_derivation_paths_are_rejected() {
+    for path in [
+        "",
+        "12381/3600/0/0/0",
+        "n/12381",
+        "m/",
+        "m//0",
+        "m/12381/3600/0/0'",
+        "m/+1",
+        "m/-1",
+        "m/4294967296",
+    ] {
+        assert!(
+            matches!(
+                path.parse::<DerivationPath>(),
+                Err(BlsError::InvalidPath(_))
+            ),
+            "{path}"
+        );
+    }
+}
+
+#[test]
+fn eip2334_paths_are_validated() {
+    for path in ["m/12381/3600/0/0", "m/12381/3600/0/0/0", "m/12381/3600/5/1"] {
+        let path: DerivationPath = path.parse().unwrap();
+        assert_eq!(path.validate_eip2334(), Ok(()));
+    }
+    for (path, error) in [
+        ("m", "expected m/12381/3600/account/use"),
+        ("m/12381/3600/0", "expected m/12381/3600/account/use"),
+        ("m/44/60/0/0/0", "purpose must be 12381"),
+        ("m/12381/60/0/0/0", "coin_type must be 3600"),
+    ] {
+        let path: DerivationPath = path.parse().unwrap();
+        let message = path.validate_eip2334().unwrap_err().to_string();
+        assert!(message.contains(error), "{message}");
+    }
+}
@@ -0,0 +1,135 @@
+//! Bearer token and client certificate authentication of the signing and admin APIs.
+
+use crate::tls::ClientCertificateAllowlist;
+use anyhow::{Context, Result};
+use rand_core::{OsRng, RngCore};
+use rocket::http::Status;
+use rocket::mtls::Certificate;
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket::State;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use subtle::ConstantTimeEq;
+
+const API_TOKEN_BYTES_LEN: usize = 32;
+
+/// Token clients must present as `Authorization: Bearer <token>`.
+pub struct ApiToken(String);
+
+impl ApiToken {
+    /// Read the token from `path`, or generate a random one and write it to `path` if the file
+    /// does not exist.
+    pub fn load_or_generate(path: &Path) -> Result<Self> {
+        if path.exists() {
+            let token = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read API token file {}", path.display()))?;
+            let token = token.trim();
+            anyhow::ensure!(
+                !token.is_empty(),
+                "API token file {} is empty",
+                path.display()
+            );
+            return Ok(ApiToken(token.to_string()));
+        }
+
+        let mut bytes = [0u8; API_TOKEN_BYTES_LEN];
+        OsRng.fill_bytes(&mut bytes);
+        let token = hex::encode(bytes);
+        write_private(path, &token)
+            .with_context(|| format!("Failed to write API token file {}", path.display()))?;
+        info!("Generated API token in {}", path.display());
+        Ok(ApiToken(token))
+    }
+
+    fn matches(&self, token: &str) -> bool {
+        self.0.as_bytes().ct_eq(token.as_bytes()).into()
+    }
+}
+
+#[cfg(unix)]
+fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+    use std::os::unix::fs::OpenOptionsExt;
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)?
+        .write_all(contents.as_bytes())
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)?
+        .write_all(contents.as_bytes())
+}
+
+/// Request guard of the authenticated routes. Requires a client certificate in the
+/// [`ClientCertificateAllowlist`] and the [`ApiToken`], when they are managed.
+pub struct Authenticated;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Authenticated {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        if let Some(allowlist) = request
+            .guard::<&State<ClientCertificateAllowlist>>()
+            .await
+            .succeeded()
+        {
+            match request.guard::<Certificate<'_>>().await.succeeded() {
+                Some(certificate) if allowlist.allows(certificate.as_bytes()) => {}
+                _ => return Outcome::Error((Status::Unauthorized, ())),
+            }
+        }
+
+        let Some(api_token) = request.guard::<&State<ApiToken>>().await.succeeded() else {
+            return Outcome::Success(Authenticated);
+        };
+
+        let token = request
+            .headers()
+            .get_one("Authorization")
+            .and_then(|authorization| authorization.strip_prefix("Bearer "));
+        match token {
+            Some(token) if api_token.matches(token.trim()) => Outcome::Success(Authenticated),
+            _ => Outcome::Error((Status::Unauthorized, ())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_token_is_generated_once() {
+        let token_dir = tempfile::tempdir().unwrap();
+        let token_path = token_dir.path().join("api_token.txt");
+
+        let api_token = ApiToken::load_or_generate(&token_path).unwrap();
+        assert_eq!(api_token.0.len(), API_TOKEN_BYTES_LEN * 2);
+        assert_eq!(fs::read_to_string(&token_path).unwrap(), api_token.0);
+
+        let reloaded = ApiToken::load_or_generate(&token_path).unwrap();
+        assert!(reloaded.matches(&api_token.0));
+        assert!(!reloaded.matches("invalid"));
+    }
+
+    #[test]
+    fn api_token_is_read_from_file() {
+        let token_dir = tempfile::tempdir().unwrap();
+        let token_path = token_dir.path().join("api_token.txt");
+        fs::write(&token_path, "my-token\n").unwrap();
+
+        let api_token = ApiToken::load_or_generate(&token_path).unwrap();
+        assert!(api_token.matches("my-token"));
+
+        fs::write(&token_path, "\n").unwrap();
+        assert!(ApiToken::load_or_generate(&token_path).is_err());
+    }
+}
@@ -0,0 +1,663 @@
+//! Command line interface and layered configuration, see [`ConfigArgs::figment`] for the
+//! precedence of the configuration sources. The signer is started when no subcommand is given.
+
+use crate::config::SignerConfig;
+use crate::deposit_data::{deposit_data_file_name, deposit_data_from_mnemonic, ExecutionAddress};
+use crate::key_loader::{password_file, write_keystore};
+use crate::key_recovery::{unix_timestamp, write_keystores, Kdf};
+use crate::signer::KeyManager;
+use anyhow::{anyhow, bail, Context, Result};
+use bls::PublicKey;
+use clap::{Args, Parser, Subcommand};
+use figment::providers::{Env, Format, Serialized, Toml, Yaml};
+use figment::{Figment, Profile};
+use serde::Serialize;
+use slashing_protection::interchange::Interchange;
+use slashing_protection::SlashingProtection;
+use specs::Spec;
+use std::fs;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+
+const ENV_PREFIX: &str = "R_SIGNER_";
+
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(flatten)]
+    pub config: ConfigArgs,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Configuration flags, available to all subcommands. They override the configuration file and
+/// environment variables.
+#[derive(Debug, Default, Args, Serialize)]
+pub struct ConfigArgs {
+    /// YAML or TOML configuration file
+    #[arg(long, global = true)]
+    #[serde(skip)]
+    pub config_file: Option<PathBuf>,
+    /// Predefined network (mainnet, minimal) or path to a custom network config yaml
+    #[arg(long, global = true)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+    /// Directory containing the keystores
+    #[arg(long, global = true)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_store_path: Option<PathBuf>,
+    /// Directory containing the keystore passwords, defaults to the keystore directory
+    #[arg(long, global = true)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_store_password_path: Option<PathBuf>,
+    /// Refuse to sign slashable blocks and attestations
+    #[arg(long, global = true, value_name = "BOOL")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slashing_protection_enabled: Option<bool>,
+    /// SQLite slashing protection database
+    #[arg(long, global = true)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slashing_protection_db_path: Option<PathBuf>,
+    /// genesis_validators_root of the network
+    #[arg(long, global = true)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub genesis_validators_root: Option<String>,
+    /// Require an API token on the signing and admin APIs
+    #[arg(long, global = true, value_name = "BOOL")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_enabled: Option<bool>,
+    /// API token file, generated if missing
+    #[arg(long, global = true)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_token_path: Option<PathBuf>,
+    /// PEM certificate chain of the server, enables TLS
+    #[arg(long, global = true)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls_cert_path: Option<PathBuf>,
+    /// PEM private key of the server
+    #[arg(long, global = true)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls_key_path: Option<PathBuf>,
+    /// PEM CA bundle client certificates must be issued by, enables mutual TLS
+    #[arg(long, global = true)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls_client_ca_path: Option<PathBuf>,
+    /// SHA-256 fingerprints of the allowed client certificates (comma separated)
+    #[arg(long, global = true, value_delimiter = ',')]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tls_client_fingerprints: Vec<String>,
+    /// Refuse sign requests carrying only a signingRoot without its payload
+    #[arg(long, global = true, value_name = "BOOL")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signing_root_strict: Option<bool>,
+    /// Refuse sign requests whose fork_info doesn't match the fork schedule of the network
+    #[arg(long, global = true, value_name = "BOOL")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validate_fork_info: Option<bool>,
+    /// HTTP listen address
+    #[arg(long, global = true)]
+    #[serde(rename = "address", skip_serializing_if = "Option::is_none")]
+    pub http_listen_address: Option<IpAddr>,
+    /// HTTP listen port
+    #[arg(long, global = true)]
+    #[serde(rename = "port", skip_serializing_if = "Option::is_none")]
+    pub http_listen_port: Option<u16>,
+}
+
+impl ConfigArgs {
+    /// Rocket's figment layered with the configuration file, environment variables and flags.
+    /// Values are merged from lowest to highest precedence from: defaults, `Rocket.toml` and
+    /// `ROCKET_` environment variables, the `--config-file` (YAML or TOML), `R_SIGNER_`
+    /// environment variables and the command line flags.
+    pub fn figment(&self) -> Result<Figment> {
+        let mut figment = rocket::Config::figment();
+        if let Some(config_file) = &self.config_file {
+            figment = figment.merge(config_file_provider(config_file)?);
+        }
+        Ok(figment
+            .merge(Env::prefixed(ENV_PREFIX).global())
+            .merge(Serialized::globals(self)))
+    }
+
+    pub fn signer_config(&self) -> Result<SignerConfig> {
+        Ok(self.figment()?.extract()?)
+    }
+}
+
+fn config_file_provider(config_file: &Path) -> Result<Figment> {
+    if !config_file.is_file() {
+        bail!("Config file {} does not exist", config_file.display());
+    }
+    let figment = match config_file.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => Figment::from(Toml::file(config_file).profile(Profile::Global)),
+        Some("yaml" | "yml") => Figment::from(Yaml::file(config_file).profile(Profile::Global)),
+        _ => bail!(
+            "Config file {} must be a .toml, .yaml or .yml file",
+            config_file.display()
+        ),
+    };
+    Ok(figment)
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Start the signer (default)
+    Run,
+    /// Inspect the keystores
+    #[command(subcommand)]
+    Keys(KeysCommand),
+    /// Import or export the slashing protection database
+    #[command(subcommand)]
+    SlashingProtection(SlashingProtectionCommand),
+}
+
+#[derive(Debug, Subcommand)]
+pub enum KeysCommand {
+    /// List the public keys and derivation paths of the keystores which can be decrypted
+    List,
+    /// Recover the keystores of validators from a BIP-39 mnemonic, offline
+    Recover {
+        /// File containing the mnemonic
+        #[arg(long)]
+        mnemonic_file: PathBuf,
+        /// File containing the BIP-39 passphrase of the mnemonic, if any
+        #[arg(long)]
+        mnemonic_passphrase_file: Option<PathBuf>,
+        /// Index of the first validator
+        #[arg(long, default_value = "0")]
+        start_index: u32,
+        /// Number of validators
+        #[arg(long)]
+        count: u32,
+        /// File containing the password the keystores are encrypted with
+        #[arg(long)]
+        password_file: PathBuf,
+        /// Key derivation function of the keystores
+        #[arg(long, value_enum, default_value = "scrypt")]
+        kdf: Kdf,
+        /// Directory the keystores are written to, defaults to the key store directory
+        #[arg(long)]
+        output_dir: Option<PathBuf>,
+        /// Directory the deposit_data-{timestamp}.json of the validators is written to
+        #[arg(long)]
+        deposit_data_dir: Option<PathBuf>,
+        /// Execution address of 0x01 withdrawal credentials, the BLS withdrawal keys of the
+        /// mnemonic are used otherwise
+        #[arg(long, requires = "deposit_data_dir")]
+        withdrawal_address: Option<ExecutionAddress>,
+        /// Deposit amount in Gwei
+        #[arg(long, default_value = "32000000000", requires = "deposit_data_dir")]
+        deposit_amount: u64,
+    },
+    /// Re-encrypt a keystore with a new password, and optionally another key derivation
+    /// function. The keystore is replaced first, then its password file. If the password file can't be replaced, the keystore is left encrypted
+    /// with the new password: copy --new-password-file to its password file
+    ChangePassword {
+        /// Keystore to re-encrypt
+        #[arg(long)]
+        keystore_file: PathBuf,
+        /// File containing the current password, defaults to the password file of the keystore
+        #[arg(long)]
+        password_file: Option<PathBuf>,
+        /// File containing the new password, written to the password file of the keystore
+        #[arg(long)]
+        new_password_file: PathBuf,
+        /// Key derivation function, defaults to the one of the keystore and its cost parameters
+        #[arg(long, value_enum)]
+        kdf: Option<Kdf>,
+        /// scrypt n or pbkdf2 c cost parameter of --kdf, defaults to the EIP-2335 recommendation
+        #[arg(long, requires = "kdf")]
+        kdf_cost: Option<u32>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SlashingProtectionCommand {
+    /// Merge an EIP-3076 interchange file into the database
+    Import {
+        #[arg(long)]
+        file: PathBuf,
+    },
+    /// Export the database to an EIP-3076 interchange file
+    Export {
+        #[arg(long)]
+        file: PathBuf,
+        /// Only export these public keys (comma separated)
+        #[arg(long, value_delimiter = ',')]
+        pubkeys: Vec<String>,
+    },
+}
+
+pub fn run_keys(command: KeysCommand, signer_config: &SignerConfig) -> Result<()> {
+    let key_store_path = signer_config
+        .key_store_path
+        .as_ref()
+        .ok_or_else(|| anyhow!("key_store_path is not configured"));
+    match command {
+        KeysCommand::List => {
+            let key_manager = KeyManager::load(
+                key_store_path?,
+                signer_config.key_store_password_path.as_deref(),
+            )?;
+            for (public_key, derivation_path) in key_manager.derivation_paths() {
+                println!("{} {}", public_key, derivation_path);
+            }
+        }
+        KeysCommand::Recover {
+            mnemonic_file,
+            mnemonic_passphrase_file,
+            start_index,
+            count,
+            password_file,
+            kdf,
+            output_dir,
+            deposit_data_dir,
+            withdrawal_address,
+            deposit_amount,
+        } => {
+            let output_dir = match &output_dir {
+                Some(output_dir) => output_dir,
+                None => key_store_path?,
+            };
+            let mnemonic = read_secret(&mnemonic_file)?;
+            let passphrase = match &mnemonic_passphrase_file {
+                Some(passphrase_file) => read_secret(passphrase_file)?,
+                None => String::new(),
+            };
+            let password = read_secret(&password_file)?;
+            let timestamp = unix_timestamp();
+            // the deposit data is checked before spending time on the keystores
+            let deposit_data = match &deposit_data_dir {
+                Some(deposit_data_dir) => {
+                    let spec = Spec::new(&signer_config.network)?;
+                    let deposit_data = deposit_data_from_mnemonic(
+                        &mnemonic,
+                        &passphrase,
+                        start_index,
+                        count,
+                        withdrawal_address,
+                        deposit_amount,
+                        &spec,
+                    )?;
+                    let file = deposit_data_dir.join(deposit_data_file_name(timestamp));
+                    if file.exists() {
+                        bail!("{} already exists", file.display());
+                    }
+                    Some((file, deposit_data))
+                }
+                None => None,
+            };
+
+            let keystores = bls_keystore::keystores_from_mnemonic(
+                &mnemonic,
+                &passphrase,
+                start_index,
+                count,
+                &password,
+                kdf.into(),
+            )?;
+            let keystore_paths = write_keystores(
+                &keystores,
+                &password,
+                output_dir,
+                signer_config.key_store_password_path.as_deref(),
+                timestamp,
+            )?;
+            for (generated, keystore_path) in keystores.iter().zip(keystore_paths) {
+                println!(
+                    "{} {} {}",
+                    generated.secret_key.public_key(),
+                    generated.path,
+                    keystore_path.display()
+                );
+            }
+            if let Some((file, deposit_data)) = deposit_data {
+                fs::write(&file, serde_json::to_string(&deposit_data)?)
+                    .with_context(|| format!("Failed to write {}", file.display()))?;
+                println!("Deposit data written to {}", file.display());
+            }
+        }
+        KeysCommand::ChangePassword {
+            keystore_file,
+            password_file: current_password_file,
+            new_password_file,
+            kdf,
+            kdf_cost,
+        } => {
+            let password_path = signer_config.key_store_password_path.as_deref();
+            let current_password_file = current_password_file
+                .unwrap_or_else(|| password_file(&keystore_file, password_path));
+            let keystore_json = fs::read_to_string(&keystore_file)
+                .with_context(|| format!("Failed to read {}", keystore_file.display()))?;
+            let new_password = read_secret(&new_password_file)?;
+            let keystore_json = bls_keystore::change_password(
+                &keystore_json,
+                &read_secret(&current_password_file)?,
+                &new_password,
+                kdf.map(|kdf| kdf.with_cost(kdf_cost)),
+            )?;
+            write_keystore(&keystore_file, password_path, &keystore_json, &new_password)?;
+            println!("Re-encrypted {}", keystore_file.display());
+        }
+    }
+    Ok(())
+}
+
+/// Content of a mnemonic, passphrase or password file, without its trailing newline.
+fn read_secret(path: &Path) -> Result<String> {
+    let secret =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(secret.trim_end_matches(['\r', '\n']).to_string())
+}
+
+pub fn run_slashing_protection(
+    command: SlashingProtectionCommand,
+    signer_config: &SignerConfig,
+) -> Result<()> {
+    let spec = Spec::new(&signer_config.network)?;
+    let genesis_validators_root = signer_config
+        .genesis_validators_root(&spec)
+        .ok_or_else(|| anyhow!("genesis_validators_root is not configured"))?;
+    let slashing_protection = SlashingProtection::open(&signer_config.slashing_protection_db_path)?;
+
+    match command {
+        SlashingProtectionCommand::Import { file } => {
+            let contents = fs::read_to_string(&file)
+                .with_context(|| format!("Failed to read {}", file.display()))?;
+            let interchange: Interchange = serde_json::from_str(&contents)
+                .with_context(|| format!("Invalid interchange file {}", file.display()))?;
+            slashing_protection.import_interchange(&interchange, &genesis_validators_root)?;
+            println!(
+                "Imported slashing protection data of {} validators",
+                interchange.data.len()
+            );
+        }
+        SlashingProtectionCommand::Export { file, pubkeys } => {
+            let pubkeys = pubkeys
+                .iter()
+                .map(|pubkey| {
+                    PublicKey::from_hex(pubkey)
+                        .map_err(|_| anyhow!("Invalid public key {}", pubkey))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let filter = (!pubkeys.is_empty()).then_some(pubkeys.as_slice());
+            let interchange =
+                slashing_protection.export_interchange(&genesis_validators_root, filter)?;
+            fs::write(&file, serde_json::to_string_pretty(&interchange)?)
+                .with_context(|| format!("Failed to write {}", file.display()))?;
+            println!(
+                "Exported slashing protection data of {} validators",
+                interchange.data.len()
+            );
+        }
+    }
+    Ok(())
+}
+
+// figment::Jail closures return figment::Error
+#[allow(clippy::result_large_err)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_loader::load_keys;
+    use bls::{DerivationPath, SecretKey};
+    use bls_keystore::KdfChoice;
+    use figment::Jail;
+
+    fn parse(args: &[&str]) -> Cli {
+        Cli::try_parse_from([&["r-signer"], args].concat()).unwrap()
+    }
+
+    #[test]
+    fn defaults_are_used_without_configuration() {
+        Jail::expect_with(|_| {
+            let cli = parse(&[]);
+            assert!(cli.command.is_none());
+            assert_eq!(cli.config.signer_config().unwrap(), SignerConfig::default());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn flags_override_environment_and_config_file() {
+        Jail::expect_with(|jail| {
+            jail.create_file(
+                "r-signer.yaml",
+                "network: minimal\nkey_store_path: /keys/from/file\nport: 9001\n",
+            )?;
+            jail.set_env("R_SIGNER_KEY_STORE_PATH", "/keys/from/env");
+            jail.set_env("R_SIGNER_SLASHING_PROTECTION_DB_PATH", "/db/from/env");
+
+            let cli = parse(&[
+                "run",
+                "--config-file",
+                "r-signer.yaml",
+                "--slashing-protection-db-path",
+                "/db/from/flag",
+                "--http-listen-address",
+                "0.0.0.0",
+            ]);
+            assert!(matches!(cli.command, Some(Command::Run)));
+            let signer_config = cli.config.signer_config().unwrap();
+            assert_eq!(signer_config.network, "minimal");
+            assert_eq!(
+                signer_config.key_store_path,
+                Some(PathBuf::from("/keys/from/env"))
+            );
+            assert_eq!(
+                signer_config.slashing_protection_db_path,
+                PathBuf::from("/db/from/flag")
+            );
+
+            let rocket_config: rocket::Config = cli.config.figment().unwrap().extract().unwrap();
+            assert_eq!(rocket_config.port, 9001);
+            assert_eq!(rocket_config.address.to_string(), "0.0.0.0");
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn toml_config_file_overrides_rocket_toml() {
+        Jail::expect_with(|jail| {
+            jail.create_file(
+                "Rocket.toml",
+                "[default]\nnetwork = \"mainnet\"\nport = 9000\n",
+            )?;
+            jail.create_file(
+                "r-signer.toml",
+                "network = \"minimal\"\nauth_enabled = false\ntls_client_fingerprints = [\"ab\"]\n",
+            )?;
+
+            let cli = parse(&["keys", "list", "--config-file", "r-signer.toml"]);
+            let signer_config = cli.config.signer_config().unwrap();
+            assert_eq!(signer_config.network, "minimal");
+            assert!(!signer_config.auth_enabled);
+            assert_eq!(signer_config.tls_client_fingerprints, vec!["ab"]);
+            let rocket_config: rocket::Config = cli.config.figment().unwrap().extract().unwrap();
+            assert_eq!(rocket_config.port, 9000);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn invalid_config_file_is_an_error() {
+        Jail::expect_with(|jail| {
+            jail.create_file("r-signer.json", "{}")?;
+            assert!(parse(&["--config-file", "missing.yaml"])
+                .config
+                .figment()
+                .is_err());
+            assert!(parse(&["--config-file", "r-signer.json"])
+                .config
+                .figment()
+                .is_err());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn keys_recover_defaults_to_scrypt_and_key_store() {
+        let cli = parse(&[
+            "keys",
+            "recover",
+            "--mnemonic-file",
+            "mnemonic.txt",
+            "--count",
+            "2",
+            "--password-file",
+            "password.txt",
+        ]);
+        let Some(Command::Keys(KeysCommand::Recover {
+            start_index,
+            count,
+            kdf,
+            output_dir,
+            mnemonic_passphrase_file,
+            ..
+        })) = cli.command
+        else {
+            panic!("{:?}", cli.command);
+        };
+        assert_eq!((start_index, count), (0, 2));
+        assert_eq!(kdf, Kdf::Scrypt);
+        assert!(output_dir.is_none());
+        assert!(mnemonic_passphrase_file.is_none());
+
+        let cli = parse(&[
+            "keys",
+            "recover",
+            "--mnemonic-file",
+            "mnemonic.txt",
+            "--start-index",
+            "10",
+            "--count",
+            "1",
+            "--password-file",
+            "password.txt",
+            "--kdf",
+            "pbkdf2",
+        ]);
+        assert!(matches!(
+            cli.command,
+            Some(Command::Keys(KeysCommand::Recover {
+                start_index: 10,
+                kdf: Kdf::Pbkdf2,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn deposit_options_require_deposit_data_dir() {
+        let recover = [
+            "r-signer",
+            "keys",
+            "recover",
+            "--mnemonic-file",
+            "mnemonic.txt",
+            "--count",
+            "1",
+            "--password-file",
+            "password.txt",
+        ];
+        let address = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        assert!(
+            Cli::try_parse_from(recover.iter().chain(&["--withdrawal-address", address])).is_err()
+        );
+
+        let cli = parse(
+            &[
+                &recover[1..],
+                &[
+                    "--deposit-data-dir",
+                    "deposits",
+                    "--withdrawal-address",
+                    address,
+                ],
+            ]
+            .concat(),
+        );
+        let Some(Command::Keys(KeysCommand::Recover {
+            deposit_data_dir,
+            withdrawal_address,
+            deposit_amount,
+            ..
+        })) = cli.command
+        else {
+            panic!("{:?}", cli.command);
+        };
+        assert_eq!(deposit_data_dir, Some(PathBuf::from("deposits")));
+        assert_eq!(withdrawal_address, Some(address.parse().unwrap()));
+        assert_eq!(deposit_amount, 32000000000);
+    }
+
+    #[test]
+    fn keys_change_password_re_encrypts_keystore() {
+        let key_store_dir = tempfile::tempdir().unwrap();
+        let password_dir = tempfile::tempdir().unwrap();
+        let keystore_path = key_store_dir.path().join("validator.json");
+        let secret_key = SecretKey::derive(&[7u8; 32], &DerivationPath::signing_key(0)).unwrap();
+        let keystore_json = bls_keystore::encrypt(
+            &secret_key.to_bytes(),
+            "old password",
+            KdfChoice::Pbkdf2 { c: 2 },
+            "m/12381/3600/0/0/0",
+            Some("validator"),
+        )
+        .unwrap();
+        write_keystore(&keystore_path, None, &keystore_json, "old password").unwrap();
+        let new_password_file = password_dir.path().join("new_password.txt");
+        fs::write(&new_password_file, "new password\n").unwrap();
+
+        let cli = parse(&[
+            "keys",
+            "change-password",
+            "--keystore-file",
+            keystore_path.to_str().unwrap(),
+            "--new-password-file",
+            new_password_file.to_str().unwrap(),
+            "--kdf",
+            "pbkdf2",
+            "--kdf-cost",
+            "4",
+        ]);
+        let Some(Command::Keys(command)) = cli.command else {
+            panic!("{:?}", cli.command);
+        };
+        run_keys(command, &SignerConfig::default()).unwrap();
+
+        let loaded_keys = load_keys(key_store_dir.path(), None).unwrap();
+        assert!(loaded_keys.failures.is_empty());
+        assert_eq!(loaded_keys.keys.len(), 1);
+        assert_eq!(
+            loaded_keys.keys[0].secret_key.public_key(),
+            secret_key.public_key()
+        );
+        let previous: serde_json::Value = serde_json::from_str(&keystore_json).unwrap();
+        let keystore: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&keystore_path).unwrap()).unwrap();
+        assert_eq!(keystore["crypto"]["kdf"]["params"]["c"], 4);
+        for field in ["pubkey", "path", "description", "uuid"] {
+            assert_eq!(keystore[field], previous[field]);
+        }
+    }
+
+    #[test]
+    fn network_flag_selects_spec() {
+        Jail::expect_with(|_| {
+            let cli = parse(&[
+                "slashing-protection",
+                "export",
+                "--file",
+                "out.json",
+                "--network",
+                "minimal",
+            ]);
+            let signer_config = cli.config.signer_config().unwrap();
+            assert!(Spec::new(&signer_config.network)
+                .unwrap()
+                .is_minimal_preset());
+            Ok(())
+        });
+    }
+}
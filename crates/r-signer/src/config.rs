@@ -0,0 +1,104 @@
+//! r-signer configuration. Values are extracted from the figment built by
+//! [`ConfigArgs::figment`](crate::cli::ConfigArgs::figment), which documents the precedence of
+//! the configuration sources.
+
+use serde::Deserialize;
+use signing_root_util::types::Hash256;
+use specs::Spec;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct SignerConfig {
+    /// Predefined network name (`mainnet`, `minimal`) or path to a custom network config yaml.
+    #[serde(default = "default_network")]
+    pub network: String,
+    /// Directory containing EIP-2335 keystores (`*.json`) and their passwords (`*.txt`).
+    pub key_store_path: Option<PathBuf>,
+    /// Directory containing the keystore passwords (`*.txt`) when not stored next to them.
+    pub key_store_password_path: Option<PathBuf>,
+    /// Refuse to sign slashable blocks and attestations.
+    #[serde(default = "default_slashing_protection_enabled")]
+    pub slashing_protection_enabled: bool,
+    /// SQLite database recording the signed blocks and attestations.
+    #[serde(default = "default_slashing_protection_db_path")]
+    pub slashing_protection_db_path: PathBuf,
+    /// genesis_validators_root of the network, validated on slashing protection imports.
+    /// Defaults to the known value of the predefined network.
+    pub genesis_validators_root: Option<Hash256>,
+    /// Require `Authorization: Bearer <token>` on the signing and admin APIs.
+    #[serde(default = "default_auth_enabled")]
+    pub auth_enabled: bool,
+    /// File containing the API token, generated on first start if missing.
+    #[serde(default = "default_api_token_path")]
+    pub api_token_path: PathBuf,
+    /// PEM certificate chain of the server. Enables TLS together with `tls_key_path`.
+    pub tls_cert_path: Option<PathBuf>,
+    /// PEM private key of the server.
+    pub tls_key_path: Option<PathBuf>,
+    /// PEM CA bundle. When set, clients must present a certificate issued by one of these CAs.
+    pub tls_client_ca_path: Option<PathBuf>,
+    /// SHA-256 fingerprints of the only client certificates accepted, requires
+    /// `tls_client_ca_path`.
+    #[serde(default)]
+    pub tls_client_fingerprints: Vec<String>,
+    /// Refuse sign requests carrying a `signingRoot` without the payload it was computed from.
+    #[serde(default = "default_signing_root_strict")]
+    pub signing_root_strict: bool,
+    /// Refuse sign requests whose `fork_info` doesn't match the fork schedule of `network` and
+    /// its `genesis_validators_root`.
+    #[serde(default)]
+    pub validate_fork_info: bool,
+}
+
+fn default_network() -> String {
+    "mainnet".to_string()
+}
+
+fn default_slashing_protection_enabled() -> bool {
+    true
+}
+
+fn default_slashing_protection_db_path() -> PathBuf {
+    PathBuf::from("slashing_protection.sqlite")
+}
+
+fn default_auth_enabled() -> bool {
+    true
+}
+
+fn default_api_token_path() -> PathBuf {
+    PathBuf::from("api_token.txt")
+}
+
+fn default_signing_root_strict() -> bool {
+    true
+}
+
+impl Default for SignerConfig {
+    fn default() -> Self {
+        SignerConfig {
+            network: default_network(),
+            key_store_path: None,
+            key_store_password_path: None,
+            slashing_protection_enabled: default_slashing_protection_enabled(),
+            slashing_protection_db_path: default_slashing_protection_db_path(),
+            genesis_validators_root: None,
+            auth_enabled: default_auth_enabled(),
+            api_token_path: default_api_token_path(),
+            tls_cert_path: None,
+            tls_key_path: None,
+            tls_client_ca_path: None,
+            tls_client_fingerprints: Vec::new(),
+            signing_root_strict: default_signing_root_strict(),
+            validate_fork_info: false,
+        }
+    }
+}
+
+impl SignerConfig {
+    /// The configured genesis_validators_root, or the known one of the predefined network.
+    pub fn genesis_validators_root(&self, spec: &Spec) -> Option<Hash256> {
+        self.genesis_validators_root
+            .or_else(|| spec.genesis_validators_root().map(Hash256::from))
+    }
+}
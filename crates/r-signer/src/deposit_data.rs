@@ -0,0 +1,333 @@
+//! Deposit data of validators, the `deposit_data-{timestamp}.json` file staking-deposit-cli
+//! generates and the launchpad submits to the deposit contract.
+
+use anyhow::{anyhow, bail, Result};
+use bls::{DerivationPath, PublicKey, SecretKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use signing_root_util::types::{DepositData, DepositMessage, Hash256};
+use signing_root_util::SigningRootUtil;
+use specs::Spec;
+use std::str::FromStr;
+use tiny_keccak::{Hasher, Keccak};
+
+/// Release of staking-deposit-cli whose deposit data format is generated. The launchpad refuses
+/// deposit data of unknown versions.
+pub const DEPOSIT_CLI_VERSION: &str = "2.7.0";
+
+const BLS_WITHDRAWAL_PREFIX: u8 = 0x00;
+const ETH1_ADDRESS_WITHDRAWAL_PREFIX: u8 = 0x01;
+
+/// Withdrawal credentials of a deposit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WithdrawalCredentials {
+    /// `0x00` credentials of a BLS withdrawal key, which has to be changed to an execution
+    /// address before withdrawing.
+    Bls(PublicKey),
+    /// `0x01` credentials withdrawing to an execution address.
+    ExecutionAddress(ExecutionAddress),
+}
+
+impl WithdrawalCredentials {
+    pub fn to_hash256(&self) -> Hash256 {
+        let mut credentials = [0u8; 32];
+        match self {
+            WithdrawalCredentials::Bls(withdrawal_pubkey) => {
+                credentials.copy_from_slice(&Sha256::digest(withdrawal_pubkey.compress()));
+                credentials[0] = BLS_WITHDRAWAL_PREFIX;
+            }
+            WithdrawalCredentials::ExecutionAddress(address) => {
+                credentials[0] = ETH1_ADDRESS_WITHDRAWAL_PREFIX;
+                credentials[12..].copy_from_slice(&address.0);
+            }
+        }
+        Hash256::from(credentials)
+    }
+}
+
+/// `0x` prefixed execution address. Mixed case addresses must carry a valid EIP-55 checksum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutionAddress(pub [u8; 20]);
+
+impl FromStr for ExecutionAddress {
+    type Err = anyhow::Error;
+
+    fn from_str(address: &str) -> Result<Self> {
+        let hex_str = address
+            .strip_prefix("0x")
+            .ok_or_else(|| anyhow!("Execution address {} must be 0x prefixed", address))?;
+        let mut bytes = [0u8; 20];
+        hex::decode_to_slice(hex_str, &mut bytes)
+            .map_err(|_| anyhow!("Invalid execution address {}", address))?;
+        let mixed_case = hex_str.chars().any(|c| c.is_ascii_lowercase())
+            && hex_str.chars().any(|c| c.is_ascii_uppercase());
+        if mixed_case && hex_str != eip55_checksum(&hex_str.to_ascii_lowercase()) {
+            bail!("Invalid EIP-55 checksum of execution address {}", address);
+        }
+        Ok(ExecutionAddress(bytes))
+    }
+}
+
+/// EIP-55 mixed case encoding of a lower case hex address.
+fn eip55_checksum(lower_hex: &str) -> String {
+    let mut hash = [0u8; 32];
+    let mut keccak = Keccak::v256();
+    keccak.update(lower_hex.as_bytes());
+    keccak.finalize(&mut hash);
+    lower_hex
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> (4 * (1 - i % 2))) & 0x0f;
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// Entry of a deposit_data.json file, hex encoded without `0x` prefix.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepositDataJson {
+    #[serde(with = "hex")]
+    pub pubkey: Vec<u8>,
+    #[serde(with = "hex")]
+    pub withdrawal_credentials: Vec<u8>,
+    /// Gwei
+    pub amount: u64,
+    #[serde(with = "hex")]
+    pub signature: Vec<u8>,
+    #[serde(with = "hex")]
+    pub deposit_message_root: Vec<u8>,
+    #[serde(with = "hex")]
+    pub deposit_data_root: Vec<u8>,
+    #[serde(with = "hex")]
+    pub fork_version: Vec<u8>,
+    pub network_name: String,
+    pub deposit_cli_version: String,
+}
+
+/// Sign the deposit of `amount` Gwei of the validator key `secret_key` on the network of
+/// `spec`.
+pub fn deposit_data(
+    secret_key: &SecretKey,
+    withdrawal_credentials: &WithdrawalCredentials,
+    amount: u64,
+    spec: &Spec,
+) -> Result<DepositDataJson> {
+    if amount < spec.min_deposit_amount() || amount > spec.max_effective_balance() {
+        bail!(
+            "Deposit amount {} Gwei must be between {} and {} Gwei",
+            amount,
+            spec.min_deposit_amount(),
+            spec.max_effective_balance()
+        );
+    }
+    let signing_root_util = SigningRootUtil::new(spec);
+    let deposit_message = DepositMessage {
+        pubkey: secret_key.public_key().compress().to_vec(),
+        withdrawal_credentials: withdrawal_credentials.to_hash256(),
+        amount,
+        genesis_fork_version: spec.genesis_fork_version(),
+    };
+    let signature = secret_key.sign(&signing_root_util.signing_root_for_deposit(&deposit_message)?);
+    let deposit_data = DepositData {
+        pubkey: deposit_message.pubkey.clone(),
+        withdrawal_credentials: deposit_message.withdrawal_credentials,
+        amount,
+        signature: signature.compress().to_vec(),
+    };
+
+    Ok(DepositDataJson {
+        deposit_message_root: signing_root_util
+            .deposit_message_root(&deposit_message)?
+            .as_bytes()
+            .to_vec(),
+        deposit_data_root: signing_root_util
+            .deposit_data_root(&deposit_data)?
+            .as_bytes()
+            .to_vec(),
+        pubkey: deposit_data.pubkey,
+        withdrawal_credentials: deposit_data.withdrawal_credentials.as_bytes().to_vec(),
+        amount,
+        signature: deposit_data.signature,
+        fork_version: spec.genesis_fork_version().to_vec(),
+        network_name: spec.config_name().to_string(),
+        deposit_cli_version: DEPOSIT_CLI_VERSION.to_string(),
+    })
+}
+
+pub fn deposit_data_file_name(timestamp: u64) -> String {
+    format!("deposit_data-{}.json", timestamp)
+}
+
+/// Deposit data of the validators `start_index..start_index + count` of a BIP-39 mnemonic. The
+/// withdrawal credentials are those of the EIP-2334 withdrawal key of each validator, unless
+/// they withdraw to `withdrawal_address`.
+pub fn deposit_data_from_mnemonic(
+    mnemonic: &str,
+    passphrase: &str,
+    start_index: u32,
+    count: u32,
+    withdrawal_address: Option<ExecutionAddress>,
+    amount: u64,
+    spec: &Spec,
+) -> Result<Vec<DepositDataJson>> {
+    let Some(end_index) = start_index.checked_add(count) else {
+        bail!(
+            "Validator index {} + {} is out of range",
+            start_index,
+            count
+        );
+    };
+    let seed = bls_keystore::mnemonic_to_seed(mnemonic, passphrase)?;
+    (start_index..end_index)
+        .map(|index| {
+            let signing_key = SecretKey::derive(&seed, &DerivationPath::signing_key(index))?;
+            let withdrawal_credentials = match withdrawal_address {
+                Some(address) => WithdrawalCredentials::ExecutionAddress(address),
+                None => WithdrawalCredentials::Bls(
+                    SecretKey::derive(&seed, &DerivationPath::withdrawal_key(index))?.public_key(),
+                ),
+            };
+            deposit_data(&signing_key, &withdrawal_credentials, amount, spec)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bls::Signature;
+    use hex_literal::hex;
+
+    // expected values were recomputed from the mnemonic outside of this crate, they are not
+    // taken from a staking-deposit-cli deposit_data file
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+    const ADDRESS: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+
+    #[test]
+    fn deposit_data_withdraws_to_bls_withdrawal_key() {
+        let spec = Spec::new("mainnet").unwrap();
+        let deposits =
+            deposit_data_from_mnemonic(MNEMONIC, "", 0, 2, None, 32000000000, &spec).unwrap();
+
+        assert_eq!(deposits.len(), 2);
+        let deposit = &deposits[0];
+        assert_eq!(deposit.pubkey, hex!("b3e445d43871965d890a398f719348a1405ac72e35b92727cc570026f54471af7ea7b2040622a8fd0b5bfb2a209b5911"));
+        assert_eq!(
+            deposit.withdrawal_credentials,
+            hex!("00eca1f12f398e3ceef109f5f76d8e99f9105e800a90390f1a18895919fd4b3b")
+        );
+        assert_eq!(
+            deposit.deposit_message_root,
+            hex!("e5f649f0154082253653461a36815b23c934a01d894fdc1c6dd91785aeac1d24")
+        );
+        assert_eq!(
+            deposit.deposit_data_root,
+            hex!("54d660cc52c015c9ceb1816c877c176b5e893a50f50ff10acfd91759448b3516")
+        );
+        let signature = Signature::from_bytes(&deposit.signature).unwrap();
+        let public_key = PublicKey::from_bytes(&deposit.pubkey).unwrap();
+        let signing_root = Hash256::from(hex!(
+            "1b552a79c495c8da2b0c2036e5f082f4856ed1896bf19457e60a5753811fab71"
+        ));
+        assert!(signature.verify(&public_key, &signing_root));
+
+        assert_eq!(
+            deposits[1].withdrawal_credentials,
+            hex!("00477335d95376155e8f46b2fc1f227335fed21c702c9b457306c68b147333d2")
+        );
+        assert_eq!(
+            deposits[1].deposit_data_root,
+            hex!("bd3e75cf9f30ae12feefd4ffc0038fff62d628b35fe426a1eead0f4d736eb8d0")
+        );
+    }
+
+    #[test]
+    fn deposit_data_withdraws_to_execution_address() {
+        let spec = Spec::new("mainnet").unwrap();
+        let address = ADDRESS.parse().unwrap();
+        let deposits =
+            deposit_data_from_mnemonic(MNEMONIC, "", 0, 1, Some(address), 1000000000, &spec)
+                .unwrap();
+
+        let deposit = &deposits[0];
+        assert_eq!(
+            deposit.withdrawal_credentials,
+            hex!("0100000000000000000000005aaeb6053f3e94c9b9a09f33669435e7ef1beaed")
+        );
+        assert_eq!(deposit.amount, 1000000000);
+        assert_eq!(
+            deposit.deposit_message_root,
+            hex!("58c81819bb2785b6f7d0488d7b7facd9badf0fe3cc5379ccb3bd07a062da46c5")
+        );
+        assert_eq!(
+            deposit.deposit_data_root,
+            hex!("cd599a55cf69b48e607c635b975e9beec4561949ce0c93711bd33c8965d8795b")
+        );
+        let signature = Signature::from_bytes(&deposit.signature).unwrap();
+        let public_key = PublicKey::from_bytes(&deposit.pubkey).unwrap();
+        let signing_root = Hash256::from(hex!(
+            "f996d0568845bd56b8542b28d13e6dba92d8c321fb0912d0e3aecb58a5f6b17d"
+        ));
+        assert!(signature.verify(&public_key, &signing_root));
+    }
+
+    #[test]
+    fn deposit_data_json_format() {
+        let spec = Spec::new("mainnet").unwrap();
+        let deposits =
+            deposit_data_from_mnemonic(MNEMONIC, "", 0, 1, None, 32000000000, &spec).unwrap();
+
+        let json = serde_json::to_value(&deposits).unwrap();
+
+        let entry = &json[0];
+        assert_eq!(entry["pubkey"], "b3e445d43871965d890a398f719348a1405ac72e35b92727cc570026f54471af7ea7b2040622a8fd0b5bfb2a209b5911");
+        assert_eq!(entry["amount"], 32000000000u64);
+        assert_eq!(entry["fork_version"], "00000000");
+        assert_eq!(entry["network_name"], "mainnet");
+        assert_eq!(entry["deposit_cli_version"], DEPOSIT_CLI_VERSION);
+        assert_eq!(
+            serde_json::from_value::<Vec<DepositDataJson>>(json).unwrap(),
+            deposits
+        );
+    }
+
+    #[test]
+    fn deposit_amount_is_bounded_by_spec() {
+        let spec = Spec::new("mainnet").unwrap();
+
+        for amount in [999999999, 32000000001] {
+            let result = deposit_data_from_mnemonic(MNEMONIC, "", 0, 1, None, amount, &spec);
+            assert!(result
+                .unwrap_err()
+                .to_string()
+                .starts_with("Deposit amount"));
+        }
+    }
+
+    #[test]
+    fn execution_address_checksum_is_verified() {
+        let address: ExecutionAddress = ADDRESS.parse().unwrap();
+        assert_eq!(address.0, hex!("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"));
+        assert_eq!(
+            ADDRESS
+                .to_ascii_lowercase()
+                .parse::<ExecutionAddress>()
+                .unwrap(),
+            address
+        );
+
+        let wrong_checksum = "0x5AAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        assert!(wrong_checksum
+            .parse::<ExecutionAddress>()
+            .unwrap_err()
+            .to_string()
+            .starts_with("Invalid EIP-55 checksum"));
+        assert!(ADDRESS[2..].parse::<ExecutionAddress>().is_err());
+        assert!("0x5aaeb6053f3e94c9".parse::<ExecutionAddress>().is_err());
+    }
+}
@@ -0,0 +1,318 @@
+//! Web3Signer compatible eth2 signing API.
+
+use crate::auth::Authenticated;
+use crate::signer::{normalize_public_key, KeyManager};
+use bls::PublicKey;
+use rocket::data::{ByteUnit, Data, Limits, ToByteUnit};
+use rocket::http::{ContentType, MediaType, Status};
+use rocket::request::Request;
+use rocket::response::{self, Responder, Response};
+use rocket::serde::json::Json;
+use rocket::State;
+use serde::{Deserialize, Serialize};
+use signing_root_util::types::{Hash256, SigningRequest};
+use signing_root_util::SigningRootUtil;
+use slashing_protection::{SlashingProtection, SlashingProtectionError};
+use specs::Spec;
+use std::io::Cursor;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ApiError {
+    #[error("Bad request: {0}")]
+    BadRequest(String),
+    #[error("Public key not found")]
+    PublicKeyNotFound,
+    #[error("Signing operation failed due to slashing protection rules: {0}")]
+    SlashingProtection(String),
+    #[error("Payload too large: {0}")]
+    PayloadTooLarge(String),
+    #[error("Internal error: {0}")]
+    Internal(String),
+}
+
+impl From<SlashingProtectionError> for ApiError {
+    fn from(e: SlashingProtectionError) -> Self {
+        match e {
+            _ if e.is_slashable() => ApiError::SlashingProtection(e.to_string()),
+            SlashingProtectionError::UnsupportedInterchangeVersion(_)
+            | SlashingProtectionError::GenesisValidatorsRootMismatch { .. }
+            | SlashingProtectionError::ValueOutOfRange(_) => ApiError::BadRequest(e.to_string()),
+            _ => ApiError::Internal(e.to_string()),
+        }
+    }
+}
+
+impl ApiError {
+    fn status(&self) -> Status {
+        match self {
+            ApiError::BadRequest(_) => Status::BadRequest,
+            ApiError::PublicKeyNotFound => Status::NotFound,
+            ApiError::SlashingProtection(_) => Status::PreconditionFailed,
+            ApiError::PayloadTooLarge(_) => Status::PayloadTooLarge,
+            ApiError::Internal(_) => Status::InternalServerError,
+        }
+    }
+}
+
+impl<'r> Responder<'r, 'static> for ApiError {
+    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'static> {
+        let message = self.to_string();
+        Response::build()
+            .status(self.status())
+            .header(ContentType::Plain)
+            .sized_body(message.len(), Cursor::new(message))
+            .ok()
+    }
+}
+
+/// Refuse sign requests carrying only a `signingRoot` without the payload it was computed from.
+pub struct SigningRootStrict(pub bool);
+
+/// Refuse sign requests whose `fork_info` is not part of the fork schedule of the network, or of
+/// another network than `genesis_validators_root` when it is known.
+pub struct ForkInfoValidation {
+    pub enabled: bool,
+    pub genesis_validators_root: Option<Hash256>,
+}
+
+/// `type` of a sign request, the artifacts the signer knows how to sign.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum ArtifactType {
+    BlockV2,
+    Attestation,
+    AggregationSlot,
+    AggregateAndProof,
+    AggregateAndProofV2,
+    RandaoReveal,
+    VoluntaryExit,
+    Deposit,
+    ValidatorRegistration,
+    SyncCommitteeMessage,
+    SyncCommitteeSelectionProof,
+    SyncCommitteeContributionAndProof,
+    BlsToExecutionChange,
+    BlobSidecar,
+    BlindedBlobSidecar,
+}
+
+impl ArtifactType {
+    fn as_str(self) -> &'static str {
+        match self {
+            ArtifactType::BlockV2 => "BLOCK_V2",
+            ArtifactType::Attestation => "ATTESTATION",
+            ArtifactType::AggregationSlot => "AGGREGATION_SLOT",
+            ArtifactType::AggregateAndProof => "AGGREGATE_AND_PROOF",
+            ArtifactType::AggregateAndProofV2 => "AGGREGATE_AND_PROOF_V2",
+            ArtifactType::RandaoReveal => "RANDAO_REVEAL",
+            ArtifactType::VoluntaryExit => "VOLUNTARY_EXIT",
+            ArtifactType::Deposit => "DEPOSIT",
+            ArtifactType::ValidatorRegistration => "VALIDATOR_REGISTRATION",
+            ArtifactType::SyncCommitteeMessage => "SYNC_COMMITTEE_MESSAGE",
+            ArtifactType::SyncCommitteeSelectionProof => "SYNC_COMMITTEE_SELECTION_PROOF",
+            ArtifactType::SyncCommitteeContributionAndProof => {
+                "SYNC_COMMITTEE_CONTRIBUTION_AND_PROOF"
+            }
+            ArtifactType::BlsToExecutionChange => "BLS_TO_EXECUTION_CHANGE",
+            ArtifactType::BlobSidecar => "BLOB_SIDECAR",
+            ArtifactType::BlindedBlobSidecar => "BLINDED_BLOB_SIDECAR",
+        }
+    }
+
+    /// Blocks and attestations can't be checked by slashing protection without their payload.
+    fn is_slashable(self) -> bool {
+        matches!(self, ArtifactType::BlockV2 | ArtifactType::Attestation)
+    }
+}
+
+/// Sign request carrying only the `signingRoot`, accepted when not in strict mode.
+#[derive(Debug, Deserialize)]
+struct SigningRootOnlyRequest {
+    #[serde(rename = "type")]
+    artifact_type: ArtifactType,
+    #[serde(rename = "signingRoot")]
+    signing_root: Hash256,
+}
+
+impl SigningRootOnlyRequest {
+    /// Keys of a sign request other than the payload.
+    const KEYS: [&'static str; 3] = ["type", "fork_info", "signingRoot"];
+
+    /// Parse `body` when it has no payload, `None` otherwise. Unknown types are bad requests.
+    fn parse(body: &serde_json::Value) -> Result<Option<Self>, ApiError> {
+        let payload_missing = body
+            .as_object()
+            .map(|body| body.keys().all(|key| Self::KEYS.contains(&key.as_str())))
+            .unwrap_or(false);
+        if payload_missing {
+            serde_json::from_value(body.clone())
+                .map(Some)
+                .map_err(|e| ApiError::BadRequest(e.to_string()))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SignatureResponse {
+    pub signature: String,
+}
+
+/// Responds with JSON when the client prefers `application/json`, plain text otherwise.
+impl<'r> Responder<'r, 'static> for SignatureResponse {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        let json_preferred = request
+            .accept()
+            .map(|accept| accept.preferred().media_type() == &MediaType::JSON)
+            .unwrap_or(false);
+
+        if json_preferred {
+            Json(self).respond_to(request)
+        } else {
+            (ContentType::Plain, self.signature).respond_to(request)
+        }
+    }
+}
+
+/// `0x` prefixed compressed public keys of the loaded keys.
+#[get("/api/v1/eth2/publicKeys")]
+pub fn public_keys(
+    _authenticated: Authenticated,
+    key_manager: &State<KeyManager>,
+) -> Json<Vec<String>> {
+    Json(key_manager.public_keys())
+}
+
+/// Read a request body of at most `limits.<name>`, `default` when it is not configured.
+pub async fn read_body(
+    body: Data<'_>,
+    limits: &Limits,
+    name: &str,
+    default: ByteUnit,
+) -> Result<String, ApiError> {
+    let limit = limits.get(name).unwrap_or(default);
+    let body = body
+        .open(limit)
+        .into_string()
+        .await
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    if !body.is_complete() {
+        return Err(ApiError::PayloadTooLarge(format!(
+            "Request body exceeds limits.{} of {}",
+            name, limit
+        )));
+    }
+    Ok(body.into_inner())
+}
+
+/// Sign request bodies may carry full blocks, they are limited by `limits.sign` instead of
+/// `limits.string`.
+#[post("/api/v1/eth2/sign/<identifier>", data = "<body>")]
+#[allow(clippy::too_many_arguments)]
+pub async fn sign(
+    _authenticated: Authenticated,
+    identifier: &str,
+    body: Data<'_>,
+    limits: &Limits,
+    spec: &State<Spec>,
+    key_manager: &State<KeyManager>,
+    slashing_protection: &State<Option<SlashingProtection>>,
+    signing_root_strict: &State<SigningRootStrict>,
+    fork_info_validation: &State<ForkInfoValidation>,
+) -> Result<SignatureResponse, ApiError> {
+    let public_key =
+        normalize_public_key(identifier).map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    let validator_public_key = key_manager
+        .public_key(&public_key)
+        .ok_or(ApiError::PublicKeyNotFound)?;
+    let body = read_body(body, limits, "sign", 10.mebibytes()).await?;
+    let body: serde_json::Value =
+        serde_json::from_str(&body).map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    let signing_root = match SigningRootOnlyRequest::parse(&body)? {
+        Some(request) => signing_root_only(
+            request,
+            signing_root_strict.0,
+            slashing_protection.is_some(),
+        )?,
+        None => {
+            let signing_request: SigningRequest =
+                serde_json::from_value(body).map_err(|e| ApiError::BadRequest(e.to_string()))?;
+            let mut signing_root_util = SigningRootUtil::new(spec);
+            if fork_info_validation.enabled {
+                signing_root_util = signing_root_util
+                    .with_fork_info_validation(fork_info_validation.genesis_validators_root);
+            }
+            let signing_root = signing_root_util
+                .verified_signing_root(&signing_request)
+                .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+            if let Some(slashing_protection) = slashing_protection.as_ref() {
+                check_slashing_protection(
+                    slashing_protection,
+                    &validator_public_key,
+                    &signing_request,
+                    &signing_root,
+                )?;
+            }
+            signing_root
+        }
+    };
+
+    let signature = key_manager
+        .sign(&public_key, &signing_root)
+        .ok_or(ApiError::PublicKeyNotFound)?;
+
+    Ok(SignatureResponse {
+        signature: signature.to_string(),
+    })
+}
+
+/// The `signingRoot` of a request without payload, refused in strict mode.
+fn signing_root_only(
+    request: SigningRootOnlyRequest,
+    strict: bool,
+    slashing_protection_enabled: bool,
+) -> Result<Hash256, ApiError> {
+    if strict {
+        return Err(ApiError::BadRequest(format!(
+            "{} sign request without payload is refused in strict mode",
+            request.artifact_type.as_str()
+        )));
+    }
+    if slashing_protection_enabled && request.artifact_type.is_slashable() {
+        return Err(ApiError::BadRequest(format!(
+            "{} sign request without payload can't be checked by slashing protection",
+            request.artifact_type.as_str()
+        )));
+    }
+    Ok(request.signing_root)
+}
+
+/// Refuse slashable blocks and attestations, recording them before they are signed.
+fn check_slashing_protection(
+    slashing_protection: &SlashingProtection,
+    public_key: &PublicKey,
+    signing_request: &SigningRequest,
+    signing_root: &Hash256,
+) -> Result<(), ApiError> {
+    match signing_request {
+        SigningRequest::BlockV2 { beacon_block, .. } => {
+            let slot = beacon_block
+                .slot()
+                .ok_or_else(|| ApiError::BadRequest("beacon_block has no slot".to_string()))?;
+            slashing_protection.check_and_insert_block_proposal(public_key, slot, signing_root)?
+        }
+        SigningRequest::Attestation { attestation, .. } => slashing_protection
+            .check_and_insert_attestation(
+                public_key,
+                attestation.source.epoch,
+                attestation.target.epoch,
+                signing_root,
+            )?,
+        _ => {}
+    }
+    Ok(())
+}
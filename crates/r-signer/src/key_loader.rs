@@ -0,0 +1,338 @@
+//! Bulk loading of EIP-2335 keystores from a directory at startup.
+
+use anyhow::{ensure, Context, Result};
+use bls::SecretKey;
+use rayon::prelude::*;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// A keystore that could not be loaded, and why. A keystore which doesn't decrypt fails with a
+/// [`bls_keystore::KeystoreError`] reason.
+#[derive(Debug)]
+pub struct KeyLoadFailure {
+    pub keystore_path: PathBuf,
+    pub reason: anyhow::Error,
+}
+
+/// A decrypted keystore.
+#[derive(Debug)]
+pub struct LoadedKey {
+    pub keystore_path: PathBuf,
+    pub derivation_path: String,
+    pub secret_key: SecretKey,
+}
+
+#[derive(Debug, Default)]
+pub struct LoadedKeys {
+    pub keys: Vec<LoadedKey>,
+    pub failures: Vec<KeyLoadFailure>,
+}
+
+/// Decrypt, in parallel, every keystore `*.json` in `key_store_path`. The password of a
+/// keystore is read from the `.txt` file with the same name, located in `password_path` when
+/// provided, or next to the keystore otherwise.
+///
+/// Only a failure to list `key_store_path` is returned as an error. Keystores which fail to
+/// load are reported individually in [`LoadedKeys::failures`].
+pub fn load_keys(key_store_path: &Path, password_path: Option<&Path>) -> Result<LoadedKeys> {
+    let mut keystore_paths = Vec::new();
+    let entries = fs::read_dir(key_store_path).with_context(|| {
+        format!(
+            "Failed to read key store directory: {}",
+            key_store_path.display()
+        )
+    })?;
+    for entry in entries {
+        let path = entry?.path();
+        if path.is_file() && path.extension().map_or(false, |ext| ext == "json") {
+            keystore_paths.push(path);
+        }
+    }
+    keystore_paths.sort();
+
+    let results: Vec<_> = keystore_paths
+        .into_par_iter()
+        .map(|keystore_path| {
+            let password_file = password_file(&keystore_path, password_path);
+            load_key(&keystore_path, &password_file).map_err(|reason| KeyLoadFailure {
+                keystore_path,
+                reason,
+            })
+        })
+        .collect();
+
+    let mut loaded_keys = LoadedKeys::default();
+    for result in results {
+        match result {
+            Ok(key) => loaded_keys.keys.push(key),
+            Err(failure) => loaded_keys.failures.push(failure),
+        }
+    }
+    Ok(loaded_keys)
+}
+
+/// Password file of a keystore, located in `password_path` when provided, or next to the
+/// keystore otherwise.
+pub fn password_file(keystore_path: &Path, password_path: Option<&Path>) -> PathBuf {
+    let password_file = keystore_path.with_extension("txt");
+    match (password_path, password_file.file_name()) {
+        (Some(password_path), Some(file_name)) => password_path.join(file_name),
+        _ => password_file,
+    }
+}
+
+/// Write a keystore and its password file, the reverse of loading it. `keystore_json` must be
+/// encrypted with `password`, callers have decrypted or just encrypted it. The keystore is
+/// written first, once it reads back unchanged, then its password file readable by the owner
+/// only. Each file is replaced atomically, see [`write_atomically`], but not both at once: when
+/// the password file can't be written, the keystore is already encrypted with `password`.
+pub fn write_keystore(
+    keystore_path: &Path,
+    password_path: Option<&Path>,
+    keystore_json: &str,
+    password: &str,
+) -> Result<()> {
+    write_atomically(keystore_path, keystore_json, 0o666, |written| {
+        ensure!(written == keystore_json, "Written keystore does not match");
+        Ok(())
+    })
+    .with_context(|| format!("Failed to write {}", keystore_path.display()))?;
+    let password_file = password_file(keystore_path, password_path);
+    write_atomically(&password_file, password, 0o600, |_| Ok(()))
+        .with_context(|| format!("Failed to write {}", password_file.display()))
+}
+
+/// Write `contents` to a `.tmp` file next to `path` created with `mode`, `verify` what was
+/// written, then rename it to `path` and sync the directory. A crash leaves either the previous
+/// or the new file, never a partially written one.
+fn write_atomically(
+    path: &Path,
+    contents: &str,
+    mode: u32,
+    verify: impl FnOnce(&str) -> Result<()>,
+) -> Result<()> {
+    let mut temp_file_name = path.file_name().context("Not a file path")?.to_os_string();
+    temp_file_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_file_name);
+    // a leftover of a previous crash would keep its permissions
+    let _ = fs::remove_file(&temp_path);
+
+    let result = create_new(&temp_path, mode)
+        .and_then(|mut file| {
+            file.write_all(contents.as_bytes())?;
+            file.sync_all()
+        })
+        .map_err(anyhow::Error::from)
+        .and_then(|()| verify(&fs::read_to_string(&temp_path)?))
+        .and_then(|()| Ok(fs::rename(&temp_path, path)?));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result?;
+    sync_dir(path.parent().context("Not a file path")?)
+}
+
+#[cfg(unix)]
+fn create_new(path: &Path, mode: u32) -> std::io::Result<File> {
+    use std::os::unix::fs::OpenOptionsExt;
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(mode)
+        .open(path)
+}
+
+#[cfg(not(unix))]
+fn create_new(path: &Path, _mode: u32) -> std::io::Result<File> {
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+}
+
+/// Persist the renames in `dir`.
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> Result<()> {
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    File::open(dir)
+        .and_then(|dir| dir.sync_all())
+        .with_context(|| format!("Failed to sync {}", dir.display()))
+}
+
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> Result<()> {
+    Ok(())
+}
+
+fn load_key(keystore_path: &Path, password_file: &Path) -> Result<LoadedKey> {
+    let keystore_json = fs::read_to_string(keystore_path).context("Failed to read keystore")?;
+    let password = fs::read_to_string(password_file)
+        .with_context(|| format!("Failed to read password file {}", password_file.display()))?;
+    let (secret_key, derivation_path) =
+        decrypt_keystore(&keystore_json, password.trim_end_matches(['\r', '\n']))?;
+    Ok(LoadedKey {
+        keystore_path: keystore_path.to_path_buf(),
+        derivation_path,
+        secret_key,
+    })
+}
+
+/// Decrypt a keystore, returning its secret key and derivation path (empty when absent).
+/// Decryption errors are [`bls_keystore::KeystoreError`]s, such as `WrongPassword`.
+pub fn decrypt_keystore(keystore_json: &str, password: &str) -> Result<(SecretKey, String)> {
+    let secret = bls_keystore::decrypt(keystore_json, password)?;
+    let secret_key = SecretKey::from_bytes(&secret)?;
+    let keystore: serde_json::Value = serde_json::from_str(keystore_json)?;
+    let derivation_path = keystore["path"].as_str().unwrap_or_default().to_string();
+    Ok((secret_key, derivation_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bls_keystore::KeystoreError;
+
+    const SCRYPT_KEYSTORE: &str = r#"{
+        "crypto": {
+            "kdf": {
+                "function": "scrypt",
+                "params": {
+                    "dklen": 32,
+                    "n": 512,
+                    "p": 1,
+                    "r": 8,
+                    "salt": "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"
+                },
+                "message": ""
+            },
+            "checksum": {
+                "function": "sha256",
+                "params": {},
+                "message": "28aea7510466a76c848c2f48649b94bd0170f90badf05480948304838d43acfc"
+            },
+            "cipher": {
+                "function": "aes-128-ctr",
+                "params": {
+                    "iv": "264daa3f303d7259501c93d997d84fe6"
+                },
+                "message": "5f4cbeea80336bd076e8f648d4c6c0f6954ae40babdbab70079fc1cd8bec4a11"
+            }
+        },
+        "pubkey": "9612d7a727c9d0a22e185a1c768478dfe919cada9266988cb32359c11f2b7b27f4ae4040902382ae2910c15e2b420d07",
+        "path": "m/12381/60/3141592653/589793238",
+        "uuid": "eb329e94-6d98-4999-a773-6162fa0dd13a",
+        "version": 4
+    }"#;
+    const PASSWORD: &str = "𝔱𝔢𝔰𝔱𝔭𝔞𝔰𝔰𝔴𝔬𝔯𝔡🔑";
+    const PUBLIC_KEY: &str = "0x9612d7a727c9d0a22e185a1c768478dfe919cada9266988cb32359c11f2b7b27f4ae4040902382ae2910c15e2b420d07";
+
+    fn failure_for<'a>(loaded_keys: &'a LoadedKeys, file_name: &str) -> &'a KeyLoadFailure {
+        loaded_keys
+            .failures
+            .iter()
+            .find(|failure| failure.keystore_path.file_name().unwrap() == file_name)
+            .unwrap()
+    }
+
+    #[test]
+    fn keys_are_loaded_and_failures_reported() {
+        let key_store_dir = tempfile::tempdir().unwrap();
+        let dir = key_store_dir.path();
+        fs::write(dir.join("valid.json"), SCRYPT_KEYSTORE).unwrap();
+        fs::write(dir.join("valid.txt"), format!("{}\n", PASSWORD)).unwrap();
+        fs::write(dir.join("wrong_password.json"), SCRYPT_KEYSTORE).unwrap();
+        fs::write(dir.join("wrong_password.txt"), "invalid").unwrap();
+        fs::write(dir.join("no_password.json"), SCRYPT_KEYSTORE).unwrap();
+        fs::write(dir.join("invalid.json"), "{}").unwrap();
+        fs::write(dir.join("invalid.txt"), PASSWORD).unwrap();
+        fs::write(dir.join("README.md"), "ignored").unwrap();
+
+        let loaded_keys = load_keys(dir, None).unwrap();
+
+        assert_eq!(loaded_keys.keys.len(), 1);
+        let key = &loaded_keys.keys[0];
+        assert_eq!(key.secret_key.public_key().to_string(), PUBLIC_KEY);
+        assert_eq!(key.keystore_path, dir.join("valid.json"));
+        assert_eq!(key.derivation_path, "m/12381/60/3141592653/589793238");
+        assert_eq!(loaded_keys.failures.len(), 3);
+        assert!(matches!(
+            failure_for(&loaded_keys, "wrong_password.json")
+                .reason
+                .downcast_ref(),
+            Some(KeystoreError::WrongPassword)
+        ));
+        assert!(failure_for(&loaded_keys, "no_password.json")
+            .reason
+            .to_string()
+            .starts_with("Failed to read password file"));
+        failure_for(&loaded_keys, "invalid.json");
+    }
+
+    #[test]
+    fn passwords_are_loaded_from_password_directory() {
+        let key_store_dir = tempfile::tempdir().unwrap();
+        let password_dir = tempfile::tempdir().unwrap();
+        fs::write(key_store_dir.path().join("validator.json"), SCRYPT_KEYSTORE).unwrap();
+        fs::write(password_dir.path().join("validator.txt"), PASSWORD).unwrap();
+
+        let loaded_keys = load_keys(key_store_dir.path(), Some(password_dir.path())).unwrap();
+
+        assert_eq!(loaded_keys.keys.len(), 1);
+        assert!(loaded_keys.failures.is_empty());
+    }
+
+    #[test]
+    fn keystore_is_replaced_atomically() {
+        let key_store_dir = tempfile::tempdir().unwrap();
+        let keystore_path = key_store_dir.path().join("validator.json");
+        fs::write(&keystore_path, "previous").unwrap();
+
+        write_keystore(&keystore_path, None, SCRYPT_KEYSTORE, PASSWORD).unwrap();
+
+        let mut file_names: Vec<_> = fs::read_dir(key_store_dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        file_names.sort();
+        assert_eq!(file_names, ["validator.json", "validator.txt"]);
+        assert_eq!(fs::read_to_string(&keystore_path).unwrap(), SCRYPT_KEYSTORE);
+        assert_eq!(
+            fs::read_to_string(keystore_path.with_extension("txt")).unwrap(),
+            PASSWORD
+        );
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let metadata = fs::metadata(keystore_path.with_extension("txt")).unwrap();
+            assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+        }
+
+        // a keystore which can't be written replaces neither file
+        let temp_path = key_store_dir.path().join("validator.json.tmp");
+        fs::create_dir(&temp_path).unwrap();
+        let error = write_keystore(&keystore_path, None, "{}", "other").unwrap_err();
+        assert!(format!("{:#}", error)
+            .starts_with(&format!("Failed to write {}", keystore_path.display())));
+        assert_eq!(fs::read_to_string(&keystore_path).unwrap(), SCRYPT_KEYSTORE);
+        assert_eq!(
+            fs::read_to_string(keystore_path.with_extension("txt")).unwrap(),
+            PASSWORD
+        );
+        fs::remove_dir(&temp_path).unwrap();
+
+        let missing_dir = key_store_dir.path().join("missing").join("validator.json");
+        assert!(write_keystore(&missing_dir, None, SCRYPT_KEYSTORE, PASSWORD).is_err());
+    }
+
+    #[test]
+    fn missing_key_store_directory_is_an_error() {
+        let result = load_keys(Path::new("/non/existent/path"), None);
+        assert!(result.is_err());
+    }
+}
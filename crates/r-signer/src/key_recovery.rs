@@ -0,0 +1,179 @@
+//! Recovery of validator keystores from a BIP-39 mnemonic, named like [staking-deposit-cli][1]
+//! names them: `keystore-m_12381_3600_{index}_0_0-{timestamp}.json`.
+//!
+//![1]: https://github.com/ethereum/staking-deposit-cli
+
+use crate::key_loader::{password_file, write_keystore};
+use anyhow::{bail, Result};
+use bls::DerivationPath;
+use bls_keystore::{GeneratedKeystore, KdfChoice};
+use clap::ValueEnum;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Key derivation function of the recovered keystores, with the cost parameters recommended by
+/// EIP-2335.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Kdf {
+    Scrypt,
+    Pbkdf2,
+}
+
+impl From<Kdf> for KdfChoice {
+    fn from(kdf: Kdf) -> Self {
+        match kdf {
+            Kdf::Scrypt => KdfChoice::SCRYPT,
+            Kdf::Pbkdf2 => KdfChoice::PBKDF2,
+        }
+    }
+}
+
+impl Kdf {
+    /// The KDF with the scrypt `n` or pbkdf2 `c` cost parameter `cost` instead of the
+    /// recommended one.
+    pub fn with_cost(self, cost: Option<u32>) -> KdfChoice {
+        match (KdfChoice::from(self), cost) {
+            (KdfChoice::Scrypt { r, p, .. }, Some(n)) => KdfChoice::Scrypt { n, r, p },
+            (KdfChoice::Pbkdf2 { .. }, Some(c)) => KdfChoice::Pbkdf2 { c },
+            (kdf_choice, None) => kdf_choice,
+        }
+    }
+}
+
+/// File name of the keystore of `path` generated at `timestamp` (seconds since the unix epoch).
+pub fn keystore_file_name(path: &DerivationPath, timestamp: u64) -> String {
+    format!(
+        "keystore-{}-{}.json",
+        path.to_string().replace('/', "_"),
+        timestamp
+    )
+}
+
+pub fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+/// Write the keystores generated at `timestamp` to `dir`, with their password files as
+/// [`password_file`] locates them. Nothing is written if one of the files already exists.
+/// Returns the keystore paths.
+pub fn write_keystores(
+    keystores: &[GeneratedKeystore],
+    password: &str,
+    dir: &Path,
+    password_path: Option<&Path>,
+    timestamp: u64,
+) -> Result<Vec<PathBuf>> {
+    let keystore_paths: Vec<PathBuf> = keystores
+        .iter()
+        .map(|generated| dir.join(keystore_file_name(&generated.path, timestamp)))
+        .collect();
+    for keystore_path in &keystore_paths {
+        let password_file = password_file(keystore_path, password_path);
+        if keystore_path.exists() || password_file.exists() {
+            bail!("{} already exists", keystore_path.display());
+        }
+    }
+
+    for (generated, keystore_path) in keystores.iter().zip(&keystore_paths) {
+        write_keystore(keystore_path, password_path, &generated.keystore, password)?;
+    }
+    Ok(keystore_paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_loader::load_keys;
+    use std::fs;
+
+    const TIMESTAMP: u64 = 1700000000;
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    fn keystores(start_index: u32, count: u32) -> Vec<GeneratedKeystore> {
+        bls_keystore::keystores_from_mnemonic(
+            MNEMONIC,
+            "",
+            start_index,
+            count,
+            "password",
+            KdfChoice::Pbkdf2 { c: 2 },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn keystore_file_name_matches_staking_deposit_cli() {
+        assert_eq!(
+            keystore_file_name(&DerivationPath::signing_key(12), TIMESTAMP),
+            "keystore-m_12381_3600_12_0_0-1700000000.json"
+        );
+    }
+
+    #[test]
+    fn recovered_keystores_are_loaded() {
+        let key_store_dir = tempfile::tempdir().unwrap();
+        let password_dir = tempfile::tempdir().unwrap();
+        let keystores = keystores(0, 3);
+
+        let keystore_paths = write_keystores(
+            &keystores,
+            "password",
+            key_store_dir.path(),
+            Some(password_dir.path()),
+            TIMESTAMP,
+        )
+        .unwrap();
+
+        assert_eq!(keystore_paths.len(), 3);
+        let file_name = keystore_paths[1].file_name().unwrap().to_str().unwrap();
+        assert_eq!(file_name, "keystore-m_12381_3600_1_0_0-1700000000.json");
+        assert_eq!(
+            fs::read_to_string(password_dir.path().join(file_name).with_extension("txt")).unwrap(),
+            "password"
+        );
+
+        let loaded_keys = load_keys(key_store_dir.path(), Some(password_dir.path())).unwrap();
+        assert!(loaded_keys.failures.is_empty());
+        let mut derivation_paths: Vec<_> = loaded_keys
+            .keys
+            .iter()
+            .map(|key| key.derivation_path.as_str())
+            .collect();
+        derivation_paths.sort();
+        assert_eq!(
+            derivation_paths,
+            [
+                "m/12381/3600/0/0/0",
+                "m/12381/3600/1/0/0",
+                "m/12381/3600/2/0/0"
+            ]
+        );
+    }
+
+    #[test]
+    fn existing_keystores_are_not_overwritten() {
+        let key_store_dir = tempfile::tempdir().unwrap();
+        let keystores = keystores(5, 2);
+        let keystore_paths = write_keystores(
+            &keystores,
+            "password",
+            key_store_dir.path(),
+            None,
+            TIMESTAMP,
+        )
+        .unwrap();
+        fs::remove_file(&keystore_paths[0]).unwrap();
+
+        // the password file of the first keystore is left behind
+        let result = write_keystores(&keystores, "other", key_store_dir.path(), None, TIMESTAMP);
+
+        assert!(result.unwrap_err().to_string().ends_with("already exists"));
+        assert!(!keystore_paths[0].exists());
+        assert_eq!(
+            fs::read_to_string(keystore_paths[1].with_extension("txt")).unwrap(),
+            "password"
+        );
+    }
+}
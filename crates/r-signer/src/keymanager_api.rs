@@ -0,0 +1,270 @@
+//! Ethereum [keymanager API][1] to list, import and delete keystores at runtime.
+//!
+//![1]: https://ethereum.github.io/keymanager-APIs/
+
+use crate::auth::Authenticated;
+use crate::eth2_api::{read_body, ApiError};
+use crate::key_loader::decrypt_keystore;
+use crate::signer::KeyManager;
+use crate::slashing_protection_api::GenesisValidatorsRoot;
+use bls::PublicKey;
+use rocket::data::{Data, Limits, ToByteUnit};
+use rocket::serde::json::Json;
+use rocket::tokio::task::spawn_blocking;
+use rocket::State;
+use serde::{Deserialize, Serialize};
+use slashing_protection::interchange::Interchange;
+use slashing_protection::SlashingProtection;
+
+/// Default `limits.keystores` in MiB.
+const KEYSTORES_LIMIT: u64 = 100;
+
+#[derive(Debug, Serialize)]
+pub struct ListKeystoresResponse {
+    pub data: Vec<KeystoreInfo>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KeystoreInfo {
+    pub validating_pubkey: String,
+    pub derivation_path: String,
+    pub readonly: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportKeystoresRequest {
+    pub keystores: Vec<String>,
+    pub passwords: Vec<String>,
+    /// JSON encoded EIP-3076 interchange.
+    pub slashing_protection: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportKeystoresResponse {
+    pub data: Vec<KeyStatus<ImportStatus>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteKeystoresRequest {
+    pub pubkeys: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeleteKeystoresResponse {
+    pub data: Vec<KeyStatus<DeleteStatus>>,
+    /// JSON encoded EIP-3076 interchange of the deleted keys. Absent when slashing protection is
+    /// disabled or the genesis_validators_root is unknown.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slashing_protection: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KeyStatus<S> {
+    pub status: S,
+    pub message: String,
+}
+
+impl<S> KeyStatus<S> {
+    fn new(status: S) -> Self {
+        KeyStatus {
+            status,
+            message: String::new(),
+        }
+    }
+
+    fn error(status: S, message: String) -> Self {
+        KeyStatus { status, message }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportStatus {
+    Imported,
+    Duplicate,
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeleteStatus {
+    Deleted,
+    NotActive,
+    NotFound,
+    Error,
+}
+
+#[get("/eth/v1/keystores")]
+pub fn list_keystores(
+    _authenticated: Authenticated,
+    key_manager: &State<KeyManager>,
+) -> Json<ListKeystoresResponse> {
+    let data = key_manager
+        .derivation_paths()
+        .into_iter()
+        .map(|(validating_pubkey, derivation_path)| KeystoreInfo {
+            validating_pubkey,
+            derivation_path,
+            readonly: false,
+        })
+        .collect();
+    Json(ListKeystoresResponse { data })
+}
+
+/// Import keystores, after importing their slashing protection history if provided. The
+/// keystores are decrypted and written on blocking threads, scrypt takes seconds per keystore.
+/// Requests carry keystores and an interchange, they are limited by `limits.keystores` instead
+/// of `limits.string`.
+#[post("/eth/v1/keystores", data = "<body>")]
+pub async fn import_keystores(
+    _authenticated: Authenticated,
+    body: Data<'_>,
+    limits: &Limits,
+    key_manager: &State<KeyManager>,
+    slashing_protection: &State<Option<SlashingProtection>>,
+    genesis_validators_root: &State<Option<GenesisValidatorsRoot>>,
+) -> Result<Json<ImportKeystoresResponse>, ApiError> {
+    let body = read_body(body, limits, "keystores", KEYSTORES_LIMIT.mebibytes()).await?;
+    let request: ImportKeystoresRequest =
+        serde_json::from_str(&body).map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    if request.keystores.len() != request.passwords.len() {
+        return Err(ApiError::BadRequest(
+            "keystores and passwords must have the same length".to_string(),
+        ));
+    }
+
+    if let Some(interchange) = &request.slashing_protection {
+        let (slashing_protection, genesis_validators_root) = match (
+            slashing_protection.as_ref(),
+            genesis_validators_root.as_ref(),
+        ) {
+            (Some(slashing_protection), Some(genesis_validators_root)) => {
+                (slashing_protection, genesis_validators_root)
+            }
+            _ => {
+                return Err(ApiError::BadRequest(
+                    "Slashing protection data can not be imported".to_string(),
+                ))
+            }
+        };
+        let interchange: Interchange =
+            serde_json::from_str(interchange).map_err(|e| ApiError::BadRequest(e.to_string()))?;
+        slashing_protection.import_interchange(&interchange, &genesis_validators_root.0)?;
+    }
+
+    let keystores: Vec<_> = request
+        .keystores
+        .into_iter()
+        .zip(request.passwords)
+        .collect();
+    let decrypted = spawn_blocking(move || {
+        keystores
+            .into_iter()
+            .map(|(keystore, password)| {
+                let decrypted = decrypt_keystore(&keystore, &password);
+                (keystore, password, decrypted)
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let mut data = Vec::with_capacity(decrypted.len());
+    for (keystore, password, decrypted) in decrypted {
+        let imported = match decrypted {
+            Ok((secret_key, derivation_path)) => {
+                let file_name = format!("{}.json", secret_key.public_key());
+                key_manager
+                    .add_keystore(secret_key, derivation_path, keystore, password, &file_name)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        data.push(match imported {
+            Ok((public_key, true)) => {
+                info!("Imported keystore {}", public_key);
+                KeyStatus::new(ImportStatus::Imported)
+            }
+            Ok((_, false)) => KeyStatus::new(ImportStatus::Duplicate),
+            Err(e) => KeyStatus::error(ImportStatus::Error, format!("{:#}", e)),
+        });
+    }
+    Ok(Json(ImportKeystoresResponse { data }))
+}
+
+/// Delete keys, returning the slashing protection history of the deleted and inactive keys.
+/// Requests are limited by `limits.keystores` like imports.
+#[delete("/eth/v1/keystores", data = "<body>")]
+pub async fn delete_keystores(
+    _authenticated: Authenticated,
+    body: Data<'_>,
+    limits: &Limits,
+    key_manager: &State<KeyManager>,
+    slashing_protection: &State<Option<SlashingProtection>>,
+    genesis_validators_root: &State<Option<GenesisValidatorsRoot>>,
+) -> Result<Json<DeleteKeystoresResponse>, ApiError> {
+    let body = read_body(body, limits, "keystores", KEYSTORES_LIMIT.mebibytes()).await?;
+    let request: DeleteKeystoresRequest =
+        serde_json::from_str(&body).map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    let slashing_protection = slashing_protection
+        .as_ref()
+        .zip(genesis_validators_root.as_ref());
+
+    let mut data = Vec::with_capacity(request.pubkeys.len());
+    let mut exported_public_keys = Vec::new();
+    for pubkey in &request.pubkeys {
+        let Ok(public_key) = PublicKey::from_hex(pubkey) else {
+            data.push(KeyStatus::error(
+                DeleteStatus::Error,
+                format!("Invalid public key {}", pubkey),
+            ));
+            continue;
+        };
+        let public_key_hex = public_key.to_string();
+
+        let status = match key_manager.delete_key(&public_key_hex) {
+            Ok(true) => {
+                info!("Deleted keystore {}", public_key_hex);
+                KeyStatus::new(DeleteStatus::Deleted)
+            }
+            Ok(false) => match slashing_protection {
+                Some((slashing_protection, genesis_validators_root)) => {
+                    let interchange = slashing_protection.export_interchange(
+                        &genesis_validators_root.0,
+                        Some(std::slice::from_ref(&public_key)),
+                    )?;
+                    if interchange.data.is_empty() {
+                        KeyStatus::new(DeleteStatus::NotFound)
+                    } else {
+                        KeyStatus::new(DeleteStatus::NotActive)
+                    }
+                }
+                None => KeyStatus::new(DeleteStatus::NotFound),
+            },
+            Err(e) => KeyStatus::error(DeleteStatus::Error, format!("{:#}", e)),
+        };
+        if matches!(
+            status.status,
+            DeleteStatus::Deleted | DeleteStatus::NotActive
+        ) {
+            exported_public_keys.push(public_key);
+        }
+        data.push(status);
+    }
+
+    let slashing_protection = match slashing_protection {
+        Some((slashing_protection, genesis_validators_root)) => {
+            let interchange = slashing_protection
+                .export_interchange(&genesis_validators_root.0, Some(&exported_public_keys))?;
+            Some(
+                serde_json::to_string(&interchange)
+                    .map_err(|e| ApiError::Internal(e.to_string()))?,
+            )
+        }
+        None => None,
+    };
+    Ok(Json(DeleteKeystoresResponse {
+        data,
+        slashing_protection,
+    }))
+}
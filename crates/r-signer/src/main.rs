@@ -1,12 +1,190 @@
 #[macro_use]
 extern crate rocket;
 
+mod auth;
+mod cli;
+mod config;
+mod deposit_data;
+mod eth2_api;
+mod key_loader;
+mod key_recovery;
+mod keymanager_api;
+mod signer;
+mod slashing_protection_api;
+mod tls;
+
+#[cfg(test)]
+mod tests;
+
+use crate::auth::ApiToken;
+use crate::cli::{Cli, Command};
+use crate::config::SignerConfig;
+use crate::eth2_api::{ForkInfoValidation, SigningRootStrict};
+use crate::signer::KeyManager;
+use crate::slashing_protection_api::GenesisValidatorsRoot;
+use crate::tls::ClientCertificateAllowlist;
+use anyhow::Result;
+use clap::Parser;
+use figment::Figment;
+use rocket::config::TlsConfig;
+use rocket::fairing::AdHoc;
+use rocket::{Build, Rocket};
+use slashing_protection::SlashingProtection;
+use specs::Spec;
+
 #[get("/upcheck")]
 fn upcheck() -> &'static str {
     "OK"
 }
 
-#[launch]
-fn rocket() -> _ {
-    rocket::build().mount("/", routes![upcheck])
+fn mount_routes(rocket: Rocket<Build>) -> Rocket<Build> {
+    rocket.mount(
+        "/",
+        routes![
+            upcheck,
+            eth2_api::public_keys,
+            eth2_api::sign,
+            slashing_protection_api::export,
+            slashing_protection_api::import,
+            keymanager_api::list_keystores,
+            keymanager_api::import_keystores,
+            keymanager_api::delete_keystores
+        ],
+    )
+}
+
+/// State shared by the routes, loaded from the signer configuration.
+struct SignerState {
+    spec: Spec,
+    key_manager: KeyManager,
+    slashing_protection: Option<SlashingProtection>,
+    signing_root_strict: SigningRootStrict,
+    fork_info_validation: ForkInfoValidation,
+    genesis_validators_root: Option<GenesisValidatorsRoot>,
+    api_token: Option<ApiToken>,
+    tls_config: Option<TlsConfig>,
+    client_certificate_allowlist: Option<ClientCertificateAllowlist>,
+}
+
+impl SignerState {
+    fn load(signer_config: &SignerConfig) -> Result<Self> {
+        let spec = Spec::new(&signer_config.network)?;
+        let key_manager = match &signer_config.key_store_path {
+            Some(key_store_path) => KeyManager::load(
+                key_store_path,
+                signer_config.key_store_password_path.as_deref(),
+            )?,
+            None => KeyManager::new(),
+        };
+        let slashing_protection = if signer_config.slashing_protection_enabled {
+            Some(SlashingProtection::open(
+                &signer_config.slashing_protection_db_path,
+            )?)
+        } else {
+            warn!("Slashing protection is disabled");
+            None
+        };
+
+        let signing_root_strict = SigningRootStrict(signer_config.signing_root_strict);
+        if !signing_root_strict.0 {
+            warn!("Sign requests carrying only a signingRoot are accepted");
+        }
+
+        let fork_info_validation = ForkInfoValidation {
+            enabled: signer_config.validate_fork_info,
+            genesis_validators_root: signer_config.genesis_validators_root(&spec),
+        };
+
+        let genesis_validators_root = signer_config
+            .genesis_validators_root(&spec)
+            .map(GenesisValidatorsRoot);
+
+        let api_token = if signer_config.auth_enabled {
+            Some(ApiToken::load_or_generate(&signer_config.api_token_path)?)
+        } else {
+            warn!("API authentication is disabled");
+            None
+        };
+
+        let tls_config = tls::tls_config(signer_config)?;
+        let client_certificate_allowlist = if signer_config.tls_client_fingerprints.is_empty() {
+            None
+        } else {
+            anyhow::ensure!(
+                signer_config.tls_client_ca_path.is_some(),
+                "tls_client_fingerprints requires tls_client_ca_path"
+            );
+            Some(ClientCertificateAllowlist::new(
+                &signer_config.tls_client_fingerprints,
+            )?)
+        };
+
+        Ok(SignerState {
+            spec,
+            key_manager,
+            slashing_protection,
+            signing_root_strict,
+            fork_info_validation,
+            genesis_validators_root,
+            api_token,
+            tls_config,
+            client_certificate_allowlist,
+        })
+    }
+
+    fn manage(self, rocket: Rocket<Build>) -> Rocket<Build> {
+        // optional state is managed as an `Option`, Rocket refuses to launch when a route
+        // uses unmanaged state
+        let mut rocket = rocket
+            .manage(self.spec)
+            .manage(self.key_manager)
+            .manage(self.slashing_protection)
+            .manage(self.signing_root_strict)
+            .manage(self.fork_info_validation)
+            .manage(self.genesis_validators_root);
+        if let Some(api_token) = self.api_token {
+            rocket = rocket.manage(api_token);
+        }
+        if let Some(client_certificate_allowlist) = self.client_certificate_allowlist {
+            rocket = rocket.manage(client_certificate_allowlist);
+        }
+        if let Some(tls_config) = self.tls_config {
+            let figment = rocket.figment().clone().merge(("tls", tls_config));
+            rocket = rocket.configure(figment);
+        }
+        rocket
+    }
+}
+
+fn rocket(figment: Figment) -> Rocket<Build> {
+    mount_routes(rocket::custom(figment)).attach(AdHoc::try_on_ignite("Signer", |rocket| async {
+        let signer_state = rocket
+            .figment()
+            .extract::<SignerConfig>()
+            .map_err(anyhow::Error::from)
+            .and_then(|signer_config| SignerState::load(&signer_config));
+
+        match signer_state {
+            Ok(signer_state) => Ok(signer_state.manage(rocket)),
+            Err(e) => {
+                error!("Unable to start signer: {:#}", e);
+                Err(rocket)
+            }
+        }
+    }))
+}
+
+#[rocket::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    match cli.command {
+        Some(Command::Keys(command)) => cli::run_keys(command, &cli.config.signer_config()?),
+        Some(Command::SlashingProtection(command)) => {
+            cli::run_slashing_protection(command, &cli.config.signer_config()?)
+        }
+        Some(Command::Run) | None => {
+            let _ = rocket(cli.config.figment()?).launch().await?;
+            Ok(())
+        }
+    }
 }
@@ -0,0 +1,235 @@
+//! In-memory BLS keys used to sign signing roots. Keys can be imported and deleted at runtime,
+//! in which case their keystores are written to, or removed from, the key store directory.
+
+use crate::key_loader::{load_keys, password_file, write_keystore};
+use anyhow::{anyhow, Context, Result};
+use bls::{PublicKey, SecretKey, Signature};
+use rocket::tokio::task::spawn_blocking;
+use signing_root_util::types::Hash256;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+
+struct Key {
+    public_key: PublicKey,
+    secret_key: SecretKey,
+    derivation_path: String,
+    /// Keystore the key was loaded from or imported to.
+    keystore_path: Option<PathBuf>,
+}
+
+/// BLS key pairs indexed by their `0x` prefixed, lower case, compressed public key.
+#[derive(Default)]
+pub struct KeyManager {
+    keys: RwLock<HashMap<String, Key>>,
+    /// Public keys whose keystore is being written by [`KeyManager::add_keystore`].
+    importing: Mutex<HashSet<String>>,
+    key_store_path: Option<PathBuf>,
+    password_path: Option<PathBuf>,
+}
+
+impl KeyManager {
+    /// Key manager without a key store directory, keys cannot be imported.
+    pub fn new() -> Self {
+        KeyManager::default()
+    }
+
+    /// Load the keystores in `key_store_path`, see [`load_keys`]. Keystores which fail to load
+    /// are logged and skipped. Imported keystores are written to `key_store_path`, and their
+    /// passwords to `password_path` when provided.
+    pub fn load(key_store_path: &Path, password_path: Option<&Path>) -> Result<Self> {
+        let loaded_keys = load_keys(key_store_path, password_path)?;
+        for failure in &loaded_keys.failures {
+            warn!(
+                "Failed to load keystore {}: {:#}",
+                failure.keystore_path.display(),
+                failure.reason
+            );
+        }
+
+        let mut keys = HashMap::new();
+        for loaded_key in loaded_keys.keys {
+            let public_key = loaded_key.secret_key.public_key();
+            keys.insert(
+                public_key.to_string(),
+                Key {
+                    public_key,
+                    secret_key: loaded_key.secret_key,
+                    derivation_path: loaded_key.derivation_path,
+                    keystore_path: Some(loaded_key.keystore_path),
+                },
+            );
+        }
+        info!(
+            "Loaded {} keys from {} ({} failed)",
+            keys.len(),
+            key_store_path.display(),
+            loaded_keys.failures.len()
+        );
+        Ok(KeyManager {
+            keys: RwLock::new(keys),
+            importing: Mutex::default(),
+            key_store_path: Some(key_store_path.to_path_buf()),
+            password_path: password_path.map(Path::to_path_buf),
+        })
+    }
+
+    /// Add a secret key which is not backed by a keystore. Returns the public key it is
+    /// indexed by.
+    #[cfg(test)]
+    pub fn add_secret_key(&self, secret_key: SecretKey) -> String {
+        let public_key = secret_key.public_key();
+        let public_key_hex = public_key.to_string();
+        self.keys
+            .write()
+            .expect("key manager lock poisoned")
+            .insert(
+                public_key_hex.clone(),
+                Key {
+                    public_key,
+                    secret_key,
+                    derivation_path: String::new(),
+                    keystore_path: None,
+                },
+            );
+        public_key_hex
+    }
+
+    /// Persist `keystore_json`, the keystore of `secret_key`, as `file_name` in the key store
+    /// directory before using the key. Returns the public key, and `false` if that key was
+    /// already loaded or is being imported, in which case nothing is written. The files are
+    /// written on a blocking thread without holding the keys, signing goes on meanwhile.
+    pub async fn add_keystore(
+        &self,
+        secret_key: SecretKey,
+        derivation_path: String,
+        keystore_json: String,
+        password: String,
+        file_name: &str,
+    ) -> Result<(String, bool)> {
+        let keystore_path = self.key_store_path()?.join(file_name);
+        let public_key = secret_key.public_key();
+        let public_key_hex = public_key.to_string();
+
+        {
+            let mut importing = self.importing.lock().expect("key manager lock poisoned");
+            let loaded = self
+                .keys
+                .read()
+                .expect("key manager lock poisoned")
+                .contains_key(&public_key_hex);
+            if loaded || !importing.insert(public_key_hex.clone()) {
+                return Ok((public_key_hex, false));
+            }
+        }
+
+        let password_path = self.password_path.clone();
+        let path = keystore_path.clone();
+        let written = spawn_blocking(move || {
+            write_keystore(&path, password_path.as_deref(), &keystore_json, &password)
+        })
+        .await
+        .unwrap_or_else(|e| Err(e.into()));
+
+        let mut importing = self.importing.lock().expect("key manager lock poisoned");
+        if written.is_ok() {
+            self.keys
+                .write()
+                .expect("key manager lock poisoned")
+                .insert(
+                    public_key_hex.clone(),
+                    Key {
+                        public_key,
+                        secret_key,
+                        derivation_path,
+                        keystore_path: Some(keystore_path),
+                    },
+                );
+        }
+        importing.remove(&public_key_hex);
+        written.map(|()| (public_key_hex, true))
+    }
+
+    fn key_store_path(&self) -> Result<&Path> {
+        self.key_store_path
+            .as_deref()
+            .ok_or_else(|| anyhow!("key_store_path is not configured"))
+    }
+
+    /// Remove the keystore and password file of the key, then stop using it. Returns `false` if
+    /// the key is not loaded. The key is kept if its keystore can't be removed, so it isn't
+    /// loaded again on restart once forgotten. A password file left behind is only logged.
+    pub fn delete_key(&self, public_key: &str) -> Result<bool> {
+        let mut keys = self.keys.write().expect("key manager lock poisoned");
+        let Some(key) = keys.get(public_key) else {
+            return Ok(false);
+        };
+        if let Some(keystore_path) = &key.keystore_path {
+            fs::remove_file(keystore_path)
+                .with_context(|| format!("Failed to remove {}", keystore_path.display()))?;
+            let password_file = password_file(keystore_path, self.password_path.as_deref());
+            if password_file.exists() {
+                if let Err(e) = fs::remove_file(&password_file) {
+                    warn!("Failed to remove {}: {}", password_file.display(), e);
+                }
+            }
+        }
+        keys.remove(public_key);
+        Ok(true)
+    }
+
+    /// Public keys of all loaded keys, derived from their secret keys, sorted.
+    pub fn public_keys(&self) -> Vec<String> {
+        let mut public_keys: Vec<String> = self
+            .keys
+            .read()
+            .expect("key manager lock poisoned")
+            .keys()
+            .cloned()
+            .collect();
+        public_keys.sort();
+        public_keys
+    }
+
+    /// Public keys and derivation paths of all loaded keys, sorted by public key.
+    pub fn derivation_paths(&self) -> Vec<(String, String)> {
+        let mut derivation_paths: Vec<(String, String)> = self
+            .keys
+            .read()
+            .expect("key manager lock poisoned")
+            .iter()
+            .map(|(public_key, key)| (public_key.clone(), key.derivation_path.clone()))
+            .collect();
+        derivation_paths.sort();
+        derivation_paths
+    }
+
+    pub fn public_key(&self, public_key: &str) -> Option<PublicKey> {
+        self.keys
+            .read()
+            .expect("key manager lock poisoned")
+            .get(public_key)
+            .map(|key| key.public_key.clone())
+    }
+
+    /// Sign `signing_root` with the key identified by `public_key`. Returns `None` if the key
+    /// is not loaded.
+    pub fn sign(&self, public_key: &str, signing_root: &Hash256) -> Option<Signature> {
+        self.keys
+            .read()
+            .expect("key manager lock poisoned")
+            .get(public_key)
+            .map(|key| key.secret_key.sign(signing_root))
+    }
+}
+
+/// Normalize a public key identifier to the `0x` prefixed lower case form used as key.
+pub fn normalize_public_key(identifier: &str) -> Result<String> {
+    let hex_str = identifier.strip_prefix("0x").unwrap_or(identifier);
+    let bytes = hex::decode(hex_str).map_err(|_| anyhow!("Invalid public key {}", identifier))?;
+    if bytes.len() != 48 {
+        return Err(anyhow!("Invalid public key {}", identifier));
+    }
+    Ok(format!("0x{}", hex::encode(bytes)))
+}
@@ -0,0 +1,76 @@
+//! Admin API to import and export the slashing protection database as an EIP-3076 interchange.
+
+use crate::auth::Authenticated;
+use crate::eth2_api::{read_body, ApiError};
+use bls::PublicKey;
+use rocket::data::{Data, Limits, ToByteUnit};
+use rocket::serde::json::Json;
+use rocket::State;
+use signing_root_util::types::Hash256;
+use slashing_protection::interchange::Interchange;
+use slashing_protection::SlashingProtection;
+
+/// genesis_validators_root of the network the signer is running on.
+pub struct GenesisValidatorsRoot(pub Hash256);
+
+/// Export the signing history of all validators, or of `pubkeys` only.
+#[get("/api/v1/admin/slashing-protection?<pubkeys>")]
+pub fn export(
+    _authenticated: Authenticated,
+    pubkeys: Vec<String>,
+    slashing_protection: &State<Option<SlashingProtection>>,
+    genesis_validators_root: &State<Option<GenesisValidatorsRoot>>,
+) -> Result<Json<Interchange>, ApiError> {
+    let (slashing_protection, genesis_validators_root) = enabled(
+        slashing_protection.as_ref(),
+        genesis_validators_root.as_ref(),
+    )?;
+    let pubkeys = pubkeys
+        .iter()
+        .map(|pubkey| parse_public_key(pubkey))
+        .collect::<Result<Vec<_>, _>>()?;
+    let filter = (!pubkeys.is_empty()).then_some(pubkeys.as_slice());
+
+    Ok(Json(
+        slashing_protection.export_interchange(&genesis_validators_root.0, filter)?,
+    ))
+}
+
+/// Merge an interchange into the slashing protection database. Interchanges carry the history
+/// of every validator, they are limited by `limits.interchange` instead of `limits.string`.
+#[post("/api/v1/admin/slashing-protection", data = "<body>")]
+pub async fn import(
+    _authenticated: Authenticated,
+    body: Data<'_>,
+    limits: &Limits,
+    slashing_protection: &State<Option<SlashingProtection>>,
+    genesis_validators_root: &State<Option<GenesisValidatorsRoot>>,
+) -> Result<(), ApiError> {
+    let (slashing_protection, genesis_validators_root) = enabled(
+        slashing_protection.as_ref(),
+        genesis_validators_root.as_ref(),
+    )?;
+    let body = read_body(body, limits, "interchange", 100.mebibytes()).await?;
+    let interchange: Interchange =
+        serde_json::from_str(&body).map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    slashing_protection.import_interchange(&interchange, &genesis_validators_root.0)?;
+    Ok(())
+}
+
+fn enabled<'a>(
+    slashing_protection: Option<&'a SlashingProtection>,
+    genesis_validators_root: Option<&'a GenesisValidatorsRoot>,
+) -> Result<(&'a SlashingProtection, &'a GenesisValidatorsRoot), ApiError> {
+    let slashing_protection = slashing_protection
+        .ok_or_else(|| ApiError::BadRequest("Slashing protection is disabled".to_string()))?;
+    let genesis_validators_root = genesis_validators_root.ok_or_else(|| {
+        ApiError::BadRequest("genesis_validators_root is not configured".to_string())
+    })?;
+    Ok((slashing_protection, genesis_validators_root))
+}
+
+fn parse_public_key(identifier: &str) -> Result<PublicKey, ApiError> {
+    PublicKey::from_hex(identifier)
+        .map_err(|_| ApiError::BadRequest(format!("Invalid public key {}", identifier)))
+}
@@ -0,0 +1,919 @@
+use super::*;
+use crate::tls::ClientCertificateAllowlist;
+use bls::{Hash256, PublicKey, SecretKey, Signature};
+use hex_literal::hex;
+use rocket::http::{Accept, ContentType, Header, Status};
+use rocket::local::blocking::{Client, LocalResponse};
+
+const SECRET: [u8; 32] = hex!("000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f");
+const PUBLIC_KEY: &str = "0x9612d7a727c9d0a22e185a1c768478dfe919cada9266988cb32359c11f2b7b27f4ae4040902382ae2910c15e2b420d07";
+
+const RANDAO_REVEAL_REQUEST: &str = r#"{
+    "type": "RANDAO_REVEAL",
+    "fork_info": {
+        "fork": {
+            "previous_version": "0x00000001",
+            "current_version": "0x00000001",
+            "epoch": "1"
+        },
+        "genesis_validators_root": "0x04700007fabc8282644aed6d1c7c9e21d38a03a0c4ba193f3afe428824b3a673"
+    },
+    "signingRoot": "0x3d047c51a8b03630781dc4c5519c17f7de87174246ff2deed0f195c6c775f91e",
+    "randao_reveal": {
+        "epoch": "3"
+    }
+}"#;
+
+const RANDAO_REVEAL_SIGNING_ROOT: [u8; 32] =
+    hex!("3d047c51a8b03630781dc4c5519c17f7de87174246ff2deed0f195c6c775f91e");
+
+fn attestation_request(beacon_block_root: &str, source_epoch: u64, target_epoch: u64) -> String {
+    format!(
+        r#"{{
+        "type": "ATTESTATION",
+        "fork_info": {{
+            "fork": {{
+                "previous_version": "0x00000001",
+                "current_version": "0x00000001",
+                "epoch": "1"
+            }},
+            "genesis_validators_root": "0x04700007fabc8282644aed6d1c7c9e21d38a03a0c4ba193f3afe428824b3a673"
+        }},
+        "attestation": {{
+            "slot": "32",
+            "index": "0",
+            "beacon_block_root": "{beacon_block_root}",
+            "source": {{
+                "epoch": "{source_epoch}",
+                "root": "0x0000000000000000000000000000000000000000000000000000000000000000"
+            }},
+            "target": {{
+                "epoch": "{target_epoch}",
+                "root": "{beacon_block_root}"
+            }}
+        }}
+    }}"#
+    )
+}
+
+fn block_request(slot: u64, body_root: &str) -> String {
+    format!(
+        r#"{{
+        "type": "BLOCK_V2",
+        "fork_info": {{
+            "fork": {{
+                "previous_version": "0x00000001",
+                "current_version": "0x00000001",
+                "epoch": "1"
+            }},
+            "genesis_validators_root": "0x04700007fabc8282644aed6d1c7c9e21d38a03a0c4ba193f3afe428824b3a673"
+        }},
+        "beacon_block": {{
+            "version": "BELLATRIX",
+            "block_header": {{
+                "slot": "{slot}",
+                "proposer_index": "5",
+                "parent_root": "0x367cbd40ac7318427aadb97345a91fa2e965daf3158d7f1846f1306305f41bef",
+                "state_root": "0xfd18cf40cc907a739be483f1ca0ee23ad65cdd3df23205eabc6d660a75d1f54e",
+                "body_root": "{body_root}"
+            }}
+        }}
+    }}"#
+    )
+}
+
+const GENESIS_VALIDATORS_ROOT: &str =
+    "0x04700007fabc8282644aed6d1c7c9e21d38a03a0c4ba193f3afe428824b3a673";
+
+const ROOT_A: &str = "0xb2eedb01adbd02c828d5eec09b4c70cbba12ffffba525ebf48aca33028e8ad89";
+const ROOT_B: &str = "0xe74b0fc13f19ae2077403afa03fdc155484f22d05d93eb084473951bb3a8d1ae";
+
+fn client() -> Client {
+    let key_manager = KeyManager::new();
+    key_manager.add_secret_key(SecretKey::from_bytes(&SECRET).unwrap());
+    client_with(key_manager)
+}
+
+fn client_with(key_manager: KeyManager) -> Client {
+    client_with_api_token(key_manager, None)
+}
+
+fn client_with_api_token(key_manager: KeyManager, api_token: Option<ApiToken>) -> Client {
+    let mut signer_state = signer_state(key_manager);
+    signer_state.api_token = api_token;
+    Client::tracked(mount_routes(signer_state.manage(rocket::build()))).unwrap()
+}
+
+fn signer_state(key_manager: KeyManager) -> SignerState {
+    SignerState {
+        spec: Spec::new("minimal").unwrap(),
+        key_manager,
+        slashing_protection: Some(SlashingProtection::open_in_memory().unwrap()),
+        signing_root_strict: SigningRootStrict(true),
+        fork_info_validation: ForkInfoValidation {
+            enabled: false,
+            genesis_validators_root: None,
+        },
+        genesis_validators_root: Some(GenesisValidatorsRoot(
+            GENESIS_VALIDATORS_ROOT.parse().unwrap(),
+        )),
+        api_token: None,
+        tls_config: None,
+        client_certificate_allowlist: None,
+    }
+}
+
+fn sign(client: &Client, body: String) -> Status {
+    client
+        .post(format!("/api/v1/eth2/sign/{}", PUBLIC_KEY))
+        .header(ContentType::JSON)
+        .body(body)
+        .dispatch()
+        .status()
+}
+
+fn assert_valid_signature(signature_hex: &str) {
+    let signature =
+        Signature::from_bytes(&hex::decode(signature_hex.strip_prefix("0x").unwrap()).unwrap())
+            .unwrap();
+    let public_key = PublicKey::from_hex(PUBLIC_KEY).unwrap();
+    assert!(signature.verify(&public_key, &Hash256::from(RANDAO_REVEAL_SIGNING_ROOT)));
+}
+
+#[test]
+fn upcheck_returns_ok() {
+    let client = client();
+    let response = client.get("/upcheck").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_string().unwrap(), "OK");
+}
+
+#[test]
+fn public_keys_lists_loaded_keys() {
+    let client = client();
+    let response = client.get("/api/v1/eth2/publicKeys").dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::JSON));
+    let public_keys: Vec<String> = response.into_json().unwrap();
+    assert_eq!(public_keys, vec![PUBLIC_KEY.to_string()]);
+}
+
+#[test]
+fn sign_returns_plain_text_signature() {
+    let client = client();
+    let response = client
+        .post(format!("/api/v1/eth2/sign/{}", PUBLIC_KEY))
+        .header(ContentType::JSON)
+        .body(RANDAO_REVEAL_REQUEST)
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::Plain));
+    assert_valid_signature(&response.into_string().unwrap());
+}
+
+#[test]
+fn sign_returns_json_signature() {
+    let client = client();
+    let response = client
+        .post(format!(
+            "/api/v1/eth2/sign/{}",
+            PUBLIC_KEY.to_uppercase().replace("0X", "0x")
+        ))
+        .header(ContentType::JSON)
+        .header(Accept::JSON)
+        .body(RANDAO_REVEAL_REQUEST)
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::JSON));
+    let body: serde_json::Value = serde_json::from_str(&response.into_string().unwrap()).unwrap();
+    assert_valid_signature(body["signature"].as_str().unwrap());
+}
+
+#[test]
+fn sign_with_unknown_public_key_returns_not_found() {
+    let client = client();
+    let response = client
+        .post("/api/v1/eth2/sign/0xa99a76ed7796f7be22d5b7e85deeb7c5677e88e511e0b337618f8c4eb61349b4bf2d153f649f7b53359fe8b94a38e44c")
+        .header(ContentType::JSON)
+        .body(RANDAO_REVEAL_REQUEST)
+        .dispatch();
+
+    assert_eq!(response.status(), Status::NotFound);
+}
+
+#[test]
+fn sign_with_invalid_body_returns_bad_request() {
+    let client = client();
+    let response = client
+        .post(format!("/api/v1/eth2/sign/{}", PUBLIC_KEY))
+        .header(ContentType::JSON)
+        .body(r#"{"type": "UNKNOWN"}"#)
+        .dispatch();
+
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[test]
+fn sign_with_mismatched_signing_root_returns_bad_request() {
+    let client = client();
+    let body = RANDAO_REVEAL_REQUEST.replace(
+        "0x3d047c51a8b03630781dc4c5519c17f7de87174246ff2deed0f195c6c775f91e",
+        ROOT_A,
+    );
+    let response = client
+        .post(format!("/api/v1/eth2/sign/{}", PUBLIC_KEY))
+        .header(ContentType::JSON)
+        .body(body)
+        .dispatch();
+
+    assert_eq!(response.status(), Status::BadRequest);
+    assert!(response.into_string().unwrap().contains("RANDAO_REVEAL"));
+}
+
+fn signing_root_only_request(artifact_type: &str) -> String {
+    format!(
+        r#"{{
+        "type": "{artifact_type}",
+        "signingRoot": "0x3d047c51a8b03630781dc4c5519c17f7de87174246ff2deed0f195c6c775f91e"
+    }}"#
+    )
+}
+
+fn non_strict_client(slashing_protection: Option<SlashingProtection>) -> Client {
+    let key_manager = KeyManager::new();
+    key_manager.add_secret_key(SecretKey::from_bytes(&SECRET).unwrap());
+    let mut signer_state = signer_state(key_manager);
+    signer_state.signing_root_strict = SigningRootStrict(false);
+    signer_state.slashing_protection = slashing_protection;
+    Client::tracked(mount_routes(signer_state.manage(rocket::build()))).unwrap()
+}
+
+#[test]
+fn signing_root_only_request_is_refused_in_strict_mode() {
+    let client = client();
+    let response = client
+        .post(format!("/api/v1/eth2/sign/{}", PUBLIC_KEY))
+        .header(ContentType::JSON)
+        .body(signing_root_only_request("RANDAO_REVEAL"))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::BadRequest);
+    assert!(response.into_string().unwrap().contains("strict mode"));
+}
+
+#[test]
+fn signing_root_only_request_is_signed_in_non_strict_mode() {
+    let client = non_strict_client(None);
+    let response = client
+        .post(format!("/api/v1/eth2/sign/{}", PUBLIC_KEY))
+        .header(ContentType::JSON)
+        .body(signing_root_only_request("RANDAO_REVEAL"))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_valid_signature(&response.into_string().unwrap());
+}
+
+#[test]
+fn signing_root_only_attestation_is_refused_with_slashing_protection() {
+    let client = non_strict_client(Some(SlashingProtection::open_in_memory().unwrap()));
+
+    assert_eq!(
+        sign(&client, signing_root_only_request("ATTESTATION")),
+        Status::BadRequest
+    );
+    assert_eq!(
+        sign(&client, signing_root_only_request("BLOCK_V2")),
+        Status::BadRequest
+    );
+    assert_eq!(
+        sign(&client, signing_root_only_request("RANDAO_REVEAL")),
+        Status::Ok
+    );
+}
+
+#[test]
+fn signing_root_only_request_of_unknown_type_is_a_bad_request() {
+    let client = non_strict_client(Some(SlashingProtection::open_in_memory().unwrap()));
+
+    for artifact_type in ["BLOCK", "ATTESTATON", "block_v2"] {
+        let response = client
+            .post(format!("/api/v1/eth2/sign/{}", PUBLIC_KEY))
+            .header(ContentType::JSON)
+            .body(signing_root_only_request(artifact_type))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::BadRequest);
+        assert!(response.into_string().unwrap().contains("unknown variant"));
+    }
+}
+
+#[test]
+fn fork_info_is_validated_against_fork_schedule_when_enabled() {
+    let key_manager = KeyManager::new();
+    key_manager.add_secret_key(SecretKey::from_bytes(&SECRET).unwrap());
+    let mut signer_state = signer_state(key_manager);
+    signer_state.fork_info_validation = ForkInfoValidation {
+        enabled: true,
+        genesis_validators_root: Some(GENESIS_VALIDATORS_ROOT.parse().unwrap()),
+    };
+    let client = Client::tracked(mount_routes(signer_state.manage(rocket::build()))).unwrap();
+
+    // the minimal network has no fork at epoch 1
+    let response = client
+        .post(format!("/api/v1/eth2/sign/{}", PUBLIC_KEY))
+        .header(ContentType::JSON)
+        .body(RANDAO_REVEAL_REQUEST)
+        .dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+    assert!(response.into_string().unwrap().contains("fork schedule"));
+
+    let genesis_fork_request = RANDAO_REVEAL_REQUEST
+        .replace(r#""epoch": "1""#, r#""epoch": "0""#)
+        .replace(
+            r#""signingRoot": "0x3d047c51a8b03630781dc4c5519c17f7de87174246ff2deed0f195c6c775f91e","#,
+            "",
+        );
+    assert_eq!(sign(&client, genesis_fork_request.clone()), Status::Ok);
+
+    let other_network_request = genesis_fork_request.replace(GENESIS_VALIDATORS_ROOT, ROOT_A);
+    assert_eq!(sign(&client, other_network_request), Status::BadRequest);
+}
+
+#[test]
+fn double_vote_is_refused_by_slashing_protection() {
+    let client = client();
+    assert_eq!(sign(&client, attestation_request(ROOT_A, 0, 1)), Status::Ok);
+    // signing the same attestation again is safe
+    assert_eq!(sign(&client, attestation_request(ROOT_A, 0, 1)), Status::Ok);
+    assert_eq!(
+        sign(&client, attestation_request(ROOT_B, 0, 1)),
+        Status::PreconditionFailed
+    );
+}
+
+#[test]
+fn surround_vote_is_refused_by_slashing_protection() {
+    let client = client();
+    assert_eq!(sign(&client, attestation_request(ROOT_A, 2, 3)), Status::Ok);
+    assert_eq!(
+        sign(&client, attestation_request(ROOT_B, 1, 4)),
+        Status::PreconditionFailed
+    );
+}
+
+#[test]
+fn double_block_proposal_is_refused_by_slashing_protection() {
+    let client = client();
+    assert_eq!(sign(&client, block_request(10, ROOT_A)), Status::Ok);
+    assert_eq!(
+        sign(&client, block_request(10, ROOT_B)),
+        Status::PreconditionFailed
+    );
+    assert_eq!(sign(&client, block_request(11, ROOT_B)), Status::Ok);
+}
+
+#[test]
+fn out_of_range_slot_is_a_bad_request() {
+    let client = client();
+    // slashing protection stores slots and epochs as SQLite integers
+    assert_eq!(
+        sign(&client, block_request(u64::MAX, ROOT_A)),
+        Status::BadRequest
+    );
+    assert_eq!(sign(&client, block_request(10, ROOT_A)), Status::Ok);
+}
+
+#[test]
+fn full_block_is_signed_and_recorded_by_slashing_protection() {
+    let client = client();
+    let full_block = include_str!("../../signing-root-util/tests/fixtures/block_v2_deneb.json");
+    assert_eq!(sign(&client, full_block.to_string()), Status::Ok);
+    assert_eq!(
+        sign(&client, block_request(42, ROOT_A)),
+        Status::PreconditionFailed
+    );
+}
+
+#[test]
+fn bls_to_execution_change_is_signed() {
+    let client = client();
+    let bls_to_execution_change =
+        include_str!("../../signing-root-util/tests/fixtures/bls_to_execution_change.json");
+    assert_eq!(
+        sign(&client, bls_to_execution_change.to_string()),
+        Status::Ok
+    );
+}
+
+fn interchange(genesis_validators_root: &str) -> String {
+    format!(
+        r#"{{
+        "metadata": {{
+            "interchange_format_version": "5",
+            "genesis_validators_root": "{genesis_validators_root}"
+        }},
+        "data": [{{
+            "pubkey": "{PUBLIC_KEY}",
+            "signed_blocks": [{{"slot": "100"}}],
+            "signed_attestations": [{{"source_epoch": "10", "target_epoch": "11"}}]
+        }}]
+    }}"#
+    )
+}
+
+#[test]
+fn slashing_protection_export_returns_signed_messages() {
+    let client = client();
+    assert_eq!(sign(&client, attestation_request(ROOT_A, 2, 3)), Status::Ok);
+
+    let response = client
+        .get(format!(
+            "/api/v1/admin/slashing-protection?pubkeys={}",
+            PUBLIC_KEY
+        ))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body: serde_json::Value = serde_json::from_str(&response.into_string().unwrap()).unwrap();
+    assert_eq!(
+        body["metadata"]["genesis_validators_root"],
+        GENESIS_VALIDATORS_ROOT
+    );
+    assert_eq!(body["data"][0]["pubkey"], PUBLIC_KEY);
+    assert_eq!(
+        body["data"][0]["signed_attestations"][0]["target_epoch"],
+        "3"
+    );
+
+    let response = client
+        .get("/api/v1/admin/slashing-protection?pubkeys=0x1234")
+        .dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[test]
+fn slashing_protection_import_is_enforced() {
+    let client = client();
+    let response = client
+        .post("/api/v1/admin/slashing-protection")
+        .header(ContentType::JSON)
+        .body(interchange(GENESIS_VALIDATORS_ROOT))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+
+    assert_eq!(
+        sign(&client, block_request(100, ROOT_A)),
+        Status::PreconditionFailed
+    );
+    assert_eq!(
+        sign(&client, attestation_request(ROOT_A, 10, 11)),
+        Status::PreconditionFailed
+    );
+    assert_eq!(sign(&client, block_request(101, ROOT_A)), Status::Ok);
+}
+
+#[test]
+fn slashing_protection_import_is_not_limited_to_8_kib() {
+    let client = client();
+    let signed_blocks = (0..1000)
+        .map(|slot| format!(r#"{{"slot": "{slot}"}}"#))
+        .collect::<Vec<_>>()
+        .join(",");
+    let body = interchange(GENESIS_VALIDATORS_ROOT).replace(r#"{"slot": "100"}"#, &signed_blocks);
+    assert!(body.len() > 8 * 1024);
+    let response = client
+        .post("/api/v1/admin/slashing-protection")
+        .header(ContentType::JSON)
+        .body(body)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+
+    assert_eq!(
+        sign(&client, block_request(999, ROOT_A)),
+        Status::PreconditionFailed
+    );
+    assert_eq!(sign(&client, block_request(1000, ROOT_A)), Status::Ok);
+}
+
+#[test]
+fn slashing_protection_import_of_other_network_returns_bad_request() {
+    let client = client();
+    let response = client
+        .post("/api/v1/admin/slashing-protection")
+        .header(ContentType::JSON)
+        .body(interchange(ROOT_A))
+        .dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+
+    assert_eq!(sign(&client, block_request(100, ROOT_A)), Status::Ok);
+}
+
+const KEYSTORE_PASSWORD: &str = "password";
+const DERIVATION_PATH: &str = "m/12381/3600/0/0/0";
+
+fn keystore() -> String {
+    bls_keystore::encrypt(
+        &SECRET,
+        KEYSTORE_PASSWORD,
+        bls_keystore::KdfChoice::Pbkdf2 { c: 512 },
+        DERIVATION_PATH,
+        None,
+    )
+    .unwrap()
+}
+
+fn statuses(response: LocalResponse) -> serde_json::Value {
+    assert_eq!(response.status(), Status::Ok);
+    response.into_json().unwrap()
+}
+
+fn delete_keystores(client: &Client) -> serde_json::Value {
+    statuses(
+        client
+            .delete("/eth/v1/keystores")
+            .header(ContentType::JSON)
+            .body(serde_json::json!({ "pubkeys": [PUBLIC_KEY] }).to_string())
+            .dispatch(),
+    )
+}
+
+#[test]
+fn keystores_are_imported_listed_and_deleted() {
+    let key_store_dir = tempfile::tempdir().unwrap();
+    let client = client_with(KeyManager::load(key_store_dir.path(), None).unwrap());
+    let keystore_path = key_store_dir.path().join(format!("{}.json", PUBLIC_KEY));
+    let password_path = key_store_dir.path().join(format!("{}.txt", PUBLIC_KEY));
+
+    let body = statuses(
+        client
+            .post("/eth/v1/keystores")
+            .header(ContentType::JSON)
+            .body(
+                serde_json::json!({
+                    "keystores": [keystore(), keystore(), keystore()],
+                    "passwords": [KEYSTORE_PASSWORD, KEYSTORE_PASSWORD, "invalid"]
+                })
+                .to_string(),
+            )
+            .dispatch(),
+    );
+    assert_eq!(body["data"][0]["status"], "imported");
+    assert_eq!(body["data"][1]["status"], "duplicate");
+    assert_eq!(body["data"][2]["status"], "error");
+    assert_eq!(body["data"][2]["message"], "Password verification failed");
+    assert!(keystore_path.exists());
+    assert_eq!(
+        std::fs::read_to_string(&password_path).unwrap(),
+        KEYSTORE_PASSWORD
+    );
+
+    let body = statuses(client.get("/eth/v1/keystores").dispatch());
+    assert_eq!(body["data"][0]["validating_pubkey"], PUBLIC_KEY);
+    assert_eq!(body["data"][0]["derivation_path"], DERIVATION_PATH);
+    assert_eq!(body["data"][0]["readonly"], false);
+    assert_eq!(sign(&client, attestation_request(ROOT_A, 2, 3)), Status::Ok);
+
+    let body = delete_keystores(&client);
+    assert_eq!(body["data"][0]["status"], "deleted");
+    let interchange: serde_json::Value =
+        serde_json::from_str(body["slashing_protection"].as_str().unwrap()).unwrap();
+    assert_eq!(interchange["data"][0]["pubkey"], PUBLIC_KEY);
+    assert_eq!(
+        interchange["data"][0]["signed_attestations"][0]["target_epoch"],
+        "3"
+    );
+    assert!(!keystore_path.exists());
+    assert!(!password_path.exists());
+    assert_eq!(
+        sign(&client, attestation_request(ROOT_A, 3, 4)),
+        Status::NotFound
+    );
+
+    // the key is gone but its slashing protection history is kept
+    let body = delete_keystores(&client);
+    assert_eq!(body["data"][0]["status"], "not_active");
+}
+
+#[test]
+fn key_is_kept_when_its_keystore_cannot_be_removed() {
+    let key_store_dir = tempfile::tempdir().unwrap();
+    let keystore_path = key_store_dir.path().join("validator.json");
+    std::fs::write(&keystore_path, keystore()).unwrap();
+    std::fs::write(keystore_path.with_extension("txt"), KEYSTORE_PASSWORD).unwrap();
+    let client = client_with(KeyManager::load(key_store_dir.path(), None).unwrap());
+    // a directory in place of the keystore can't be removed with remove_file
+    std::fs::remove_file(&keystore_path).unwrap();
+    std::fs::create_dir(&keystore_path).unwrap();
+
+    let body = delete_keystores(&client);
+
+    assert_eq!(body["data"][0]["status"], "error");
+    assert!(keystore_path.with_extension("txt").exists());
+    assert_eq!(sign(&client, attestation_request(ROOT_A, 2, 3)), Status::Ok);
+}
+
+#[test]
+fn unknown_keystore_is_not_found() {
+    let key_store_dir = tempfile::tempdir().unwrap();
+    let client = client_with(KeyManager::load(key_store_dir.path(), None).unwrap());
+
+    let body = delete_keystores(&client);
+    assert_eq!(body["data"][0]["status"], "not_found");
+    let interchange: serde_json::Value =
+        serde_json::from_str(body["slashing_protection"].as_str().unwrap()).unwrap();
+    assert_eq!(interchange["data"], serde_json::json!([]));
+}
+
+#[test]
+fn keystore_requests_are_not_limited_to_8_kib() {
+    let key_store_dir = tempfile::tempdir().unwrap();
+    let client = client_with(KeyManager::load(key_store_dir.path(), None).unwrap());
+
+    let body = serde_json::json!({
+        "keystores": vec![keystore(); 10],
+        "passwords": vec![KEYSTORE_PASSWORD; 10]
+    })
+    .to_string();
+    assert!(body.len() > 8 * 1024);
+    let body = statuses(
+        client
+            .post("/eth/v1/keystores")
+            .header(ContentType::JSON)
+            .body(body)
+            .dispatch(),
+    );
+    assert_eq!(body["data"][0]["status"], "imported");
+    assert_eq!(body["data"][9]["status"], "duplicate");
+
+    let body = serde_json::json!({ "pubkeys": vec![PUBLIC_KEY; 100] }).to_string();
+    assert!(body.len() > 8 * 1024);
+    let body = statuses(
+        client
+            .delete("/eth/v1/keystores")
+            .header(ContentType::JSON)
+            .body(body)
+            .dispatch(),
+    );
+    assert_eq!(body["data"][0]["status"], "deleted");
+    assert_eq!(body["data"][99]["status"], "not_found");
+}
+
+#[test]
+fn keystores_are_imported_with_slashing_protection() {
+    let key_store_dir = tempfile::tempdir().unwrap();
+    let client = client_with(KeyManager::load(key_store_dir.path(), None).unwrap());
+
+    let body = statuses(
+        client
+            .post("/eth/v1/keystores")
+            .header(ContentType::JSON)
+            .body(
+                serde_json::json!({
+                    "keystores": [keystore()],
+                    "passwords": [KEYSTORE_PASSWORD],
+                    "slashing_protection": interchange(GENESIS_VALIDATORS_ROOT)
+                })
+                .to_string(),
+            )
+            .dispatch(),
+    );
+    assert_eq!(body["data"][0]["status"], "imported");
+    assert_eq!(
+        sign(&client, block_request(100, ROOT_A)),
+        Status::PreconditionFailed
+    );
+}
+
+#[test]
+fn keystores_are_not_imported_without_key_store() {
+    let client = client_with(KeyManager::new());
+
+    let body = statuses(
+        client
+            .post("/eth/v1/keystores")
+            .header(ContentType::JSON)
+            .body(
+                serde_json::json!({
+                    "keystores": [keystore()],
+                    "passwords": [KEYSTORE_PASSWORD]
+                })
+                .to_string(),
+            )
+            .dispatch(),
+    );
+    assert_eq!(body["data"][0]["status"], "error");
+    assert_eq!(sign(&client, block_request(100, ROOT_A)), Status::NotFound);
+}
+
+#[test]
+fn authenticated_routes_require_api_token() {
+    let token_dir = tempfile::tempdir().unwrap();
+    let token_path = token_dir.path().join("api_token.txt");
+    let api_token = ApiToken::load_or_generate(&token_path).unwrap();
+    let token = std::fs::read_to_string(&token_path).unwrap();
+    let key_manager = KeyManager::new();
+    key_manager.add_secret_key(SecretKey::from_bytes(&SECRET).unwrap());
+    let client = client_with_api_token(key_manager, Some(api_token));
+
+    let sign = |authorization: Option<String>| {
+        let mut request = client
+            .post(format!("/api/v1/eth2/sign/{}", PUBLIC_KEY))
+            .header(ContentType::JSON)
+            .body(RANDAO_REVEAL_REQUEST);
+        if let Some(authorization) = authorization {
+            request = request.header(Header::new("Authorization", authorization));
+        }
+        request.dispatch().status()
+    };
+    assert_eq!(sign(None), Status::Unauthorized);
+    assert_eq!(
+        sign(Some("Bearer invalid".to_string())),
+        Status::Unauthorized
+    );
+    assert_eq!(sign(Some(token.clone())), Status::Unauthorized);
+    assert_eq!(sign(Some(format!("Bearer {}", token))), Status::Ok);
+
+    for path in [
+        "/api/v1/eth2/publicKeys",
+        "/eth/v1/keystores",
+        "/api/v1/admin/slashing-protection",
+    ] {
+        assert_eq!(client.get(path).dispatch().status(), Status::Unauthorized);
+        let response = client
+            .get(path)
+            .header(Header::new("Authorization", format!("Bearer {}", token)))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+    }
+    assert_eq!(client.get("/upcheck").dispatch().status(), Status::Ok);
+}
+
+fn certificate(common_name: &str, is_ca: bool) -> rcgen::Certificate {
+    let mut params = rcgen::CertificateParams::new(vec![common_name.to_string()]);
+    params.distinguished_name = rcgen::DistinguishedName::new();
+    params
+        .distinguished_name
+        .push(rcgen::DnType::CommonName, common_name);
+    if is_ca {
+        params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+    }
+    rcgen::Certificate::from_params(params).unwrap()
+}
+
+/// PEM and DER encodings of a certificate issued by `ca`.
+fn issue_certificate(
+    certificate: &rcgen::Certificate,
+    ca: &rcgen::Certificate,
+) -> (String, Vec<u8>) {
+    let pem = certificate.serialize_pem_with_signer(ca).unwrap();
+    let der = rustls_pemfile::certs(&mut pem.as_bytes())
+        .unwrap()
+        .remove(0);
+    (pem, der)
+}
+
+#[test]
+fn authenticated_routes_require_allowed_client_certificate() {
+    let ca = certificate("r-signer test CA", true);
+    let (allowed_pem, allowed_der) = issue_certificate(&certificate("allowed", false), &ca);
+    let (other_pem, _) = issue_certificate(&certificate("other", false), &ca);
+    let key_manager = KeyManager::new();
+    key_manager.add_secret_key(SecretKey::from_bytes(&SECRET).unwrap());
+    let mut signer_state = signer_state(key_manager);
+    signer_state.client_certificate_allowlist = Some(
+        ClientCertificateAllowlist::new(&[hex::encode(tls::fingerprint(&allowed_der))]).unwrap(),
+    );
+    let client = Client::tracked(mount_routes(signer_state.manage(rocket::build()))).unwrap();
+
+    let public_keys = |identity: Option<&str>| {
+        let mut request = client.get("/api/v1/eth2/publicKeys");
+        if let Some(identity) = identity {
+            request = request.identity(identity.as_bytes());
+        }
+        request.dispatch().status()
+    };
+    assert_eq!(public_keys(Some(&allowed_pem)), Status::Ok);
+    assert_eq!(public_keys(Some(&other_pem)), Status::Unauthorized);
+    assert_eq!(public_keys(None), Status::Unauthorized);
+    assert_eq!(client.get("/upcheck").dispatch().status(), Status::Ok);
+}
+
+async fn https_upcheck(
+    port: u16,
+    ca_der: &[u8],
+    client_identity: Option<(Vec<u8>, Vec<u8>)>,
+) -> std::io::Result<String> {
+    use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let mut roots = rustls::RootCertStore::empty();
+    roots.add(&rustls::Certificate(ca_der.to_vec())).unwrap();
+    let builder = rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots);
+    let config = match client_identity {
+        Some((cert_der, key_der)) => builder
+            .with_client_auth_cert(
+                vec![rustls::Certificate(cert_der)],
+                rustls::PrivateKey(key_der),
+            )
+            .unwrap(),
+        None => builder.with_no_client_auth(),
+    };
+
+    let connector = tokio_rustls::TlsConnector::from(std::sync::Arc::new(config));
+    let stream = rocket::tokio::net::TcpStream::connect(("127.0.0.1", port)).await?;
+    let server_name = rustls::ServerName::try_from("localhost").unwrap();
+    let mut stream = connector.connect(server_name, stream).await?;
+    stream
+        .write_all(b"GET /upcheck HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n")
+        .await?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await?;
+    Ok(response)
+}
+
+#[rocket::async_test]
+async fn mutual_tls_requires_client_certificate_from_ca() {
+    let dir = tempfile::tempdir().unwrap();
+    let ca = certificate("r-signer test CA", true);
+    let ca_pem = ca.serialize_pem().unwrap();
+    let ca_der = rustls_pemfile::certs(&mut ca_pem.as_bytes())
+        .unwrap()
+        .remove(0);
+    let server = certificate("localhost", false);
+    let client = certificate("client", false);
+    let (_, client_der) = issue_certificate(&client, &ca);
+    let untrusted_ca = certificate("untrusted CA", true);
+    let (_, untrusted_der) = issue_certificate(&client, &untrusted_ca);
+
+    let signer_config = SignerConfig {
+        tls_cert_path: Some(dir.path().join("cert.pem")),
+        tls_key_path: Some(dir.path().join("key.pem")),
+        tls_client_ca_path: Some(dir.path().join("ca.pem")),
+        ..SignerConfig::default()
+    };
+    std::fs::write(
+        dir.path().join("cert.pem"),
+        server.serialize_pem_with_signer(&ca).unwrap(),
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("key.pem"),
+        server.serialize_private_key_pem(),
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("ca.pem"), &ca_pem).unwrap();
+
+    let mut signer_state = signer_state(KeyManager::new());
+    signer_state.tls_config = tls::tls_config(&signer_config).unwrap();
+    let figment = rocket::Config::figment()
+        .merge(("address", "127.0.0.1"))
+        .merge(("port", 0))
+        .merge(("log_level", "off"));
+    let (port_sender, port_receiver) = rocket::tokio::sync::oneshot::channel();
+    let rocket = mount_routes(signer_state.manage(rocket::custom(figment)))
+        .attach(AdHoc::on_liftoff("Port", |rocket| {
+            Box::pin(async move {
+                port_sender.send(rocket.config().port).unwrap();
+            })
+        }))
+        .ignite()
+        .await
+        .unwrap();
+    let shutdown = rocket.shutdown();
+    let server = rocket::tokio::spawn(rocket.launch());
+    let port = port_receiver.await.unwrap();
+
+    let client_key_der = client.serialize_private_key_der();
+    let response = https_upcheck(port, &ca_der, Some((client_der, client_key_der.clone())))
+        .await
+        .unwrap();
+    assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+    assert!(response.ends_with("OK"));
+
+    for client_identity in [None, Some((untrusted_der, client_key_der))] {
+        let response = https_upcheck(port, &ca_der, client_identity).await;
+        assert!(response.map_or(true, |response| response.is_empty()));
+    }
+
+    shutdown.notify();
+    server.await.unwrap().unwrap();
+}
+
+#[test]
+fn signer_starts_without_optional_state() {
+    let key_manager = KeyManager::new();
+    key_manager.add_secret_key(SecretKey::from_bytes(&SECRET).unwrap());
+    let mut signer_state = signer_state(key_manager);
+    signer_state.slashing_protection = None;
+    signer_state.genesis_validators_root = None;
+    let client = Client::tracked(mount_routes(signer_state.manage(rocket::build()))).unwrap();
+
+    assert_eq!(sign(&client, block_request(100, ROOT_A)), Status::Ok);
+    assert_eq!(sign(&client, block_request(100, ROOT_B)), Status::Ok);
+    let response = client.get("/api/v1/admin/slashing-protection").dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+}
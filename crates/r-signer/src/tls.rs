@@ -0,0 +1,119 @@
+//! TLS and mutual TLS configuration of the HTTP server.
+
+use crate::config::SignerConfig;
+use anyhow::{anyhow, bail, Result};
+use rocket::config::{MutualTls, TlsConfig};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+
+/// Rocket TLS configuration from the signer configuration, `None` when TLS is not configured.
+/// Client certificates are required, and verified against `tls_client_ca_path`, when it is set.
+pub fn tls_config(signer_config: &SignerConfig) -> Result<Option<TlsConfig>> {
+    let (cert_path, key_path) = match (&signer_config.tls_cert_path, &signer_config.tls_key_path) {
+        (Some(cert_path), Some(key_path)) => (cert_path, key_path),
+        (None, None) => {
+            if signer_config.tls_client_ca_path.is_some() {
+                bail!("tls_client_ca_path requires tls_cert_path and tls_key_path");
+            }
+            return Ok(None);
+        }
+        _ => bail!("tls_cert_path and tls_key_path must be configured together"),
+    };
+
+    let tls_config = TlsConfig::from_paths(cert_path, key_path);
+    match &signer_config.tls_client_ca_path {
+        Some(ca_path) => Ok(Some(
+            tls_config.with_mutual(MutualTls::from_path(ca_path).mandatory(true)),
+        )),
+        None => Ok(Some(tls_config)),
+    }
+}
+
+/// SHA-256 fingerprints of the DER encoded client certificates allowed to use the signer.
+/// Client certificates must still be verified by the CA bundle during the TLS handshake, a
+/// self-signed client certificate can be its own CA.
+pub struct ClientCertificateAllowlist(HashSet<[u8; 32]>);
+
+impl ClientCertificateAllowlist {
+    /// Parse hex encoded fingerprints, optionally `:` separated (`AB:CD:..`).
+    pub fn new(fingerprints: &[String]) -> Result<Self> {
+        let mut allowlist = HashSet::with_capacity(fingerprints.len());
+        for fingerprint in fingerprints {
+            let hex_str = fingerprint.replace(':', "");
+            let bytes = hex::decode(hex_str.trim())
+                .ok()
+                .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+                .ok_or_else(|| anyhow!("Invalid SHA-256 fingerprint {}", fingerprint))?;
+            allowlist.insert(bytes);
+        }
+        Ok(ClientCertificateAllowlist(allowlist))
+    }
+
+    pub fn allows(&self, certificate_der: &[u8]) -> bool {
+        self.0.contains(&fingerprint(certificate_der))
+    }
+}
+
+pub fn fingerprint(certificate_der: &[u8]) -> [u8; 32] {
+    Sha256::digest(certificate_der).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const FINGERPRINT: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+    fn signer_config(cert: bool, key: bool, ca: bool) -> SignerConfig {
+        SignerConfig {
+            tls_cert_path: cert.then(|| PathBuf::from("cert.pem")),
+            tls_key_path: key.then(|| PathBuf::from("key.pem")),
+            tls_client_ca_path: ca.then(|| PathBuf::from("ca.pem")),
+            ..SignerConfig::default()
+        }
+    }
+
+    #[test]
+    fn tls_is_configured_from_cert_and_key() {
+        assert!(tls_config(&signer_config(false, false, false))
+            .unwrap()
+            .is_none());
+
+        let tls_config = tls_config(&signer_config(true, true, false))
+            .unwrap()
+            .unwrap();
+        assert!(tls_config.mutual().is_none());
+
+        let tls_config = super::tls_config(&signer_config(true, true, true))
+            .unwrap()
+            .unwrap();
+        assert!(tls_config.mutual().unwrap().mandatory);
+    }
+
+    #[test]
+    fn incomplete_tls_configuration_is_an_error() {
+        assert!(tls_config(&signer_config(true, false, false)).is_err());
+        assert!(tls_config(&signer_config(false, true, false)).is_err());
+        assert!(tls_config(&signer_config(false, false, true)).is_err());
+    }
+
+    #[test]
+    fn fingerprints_are_parsed() {
+        let colon_separated = FINGERPRINT
+            .as_bytes()
+            .chunks(2)
+            .map(|byte| std::str::from_utf8(byte).unwrap().to_uppercase())
+            .collect::<Vec<_>>()
+            .join(":");
+        for fingerprint in [FINGERPRINT.to_string(), colon_separated] {
+            let allowlist = ClientCertificateAllowlist::new(&[fingerprint]).unwrap();
+            // SHA-256 of empty input
+            assert!(allowlist.allows(b""));
+            assert!(!allowlist.allows(b"certificate"));
+        }
+
+        assert!(ClientCertificateAllowlist::new(&["abcd".to_string()]).is_err());
+        assert!(ClientCertificateAllowlist::new(&["xyz".to_string()]).is_err());
+    }
+}
@@ -1,6 +1,11 @@
 use super::*;
 use crate::Hash256;
 use anyhow::Result;
+use specs::{
+    BYTES_PER_LOGS_BLOOM, MAX_ATTESTATIONS, MAX_ATTESTER_SLASHINGS, MAX_BLS_TO_EXECUTION_CHANGES,
+    MAX_BYTES_PER_TRANSACTION, MAX_DEPOSITS, MAX_EXTRA_DATA_BYTES, MAX_PROPOSER_SLASHINGS,
+    MAX_TRANSACTIONS_PER_PAYLOAD, MAX_VALIDATORS_PER_COMMITTEE, MAX_VOLUNTARY_EXITS,
+};
 use ssz_rs::prelude::*;
 
 pub trait SigningRoot {
@@ -147,54 +152,134 @@ impl SigningRoot for InternalAttestationData {
 }
 
 #[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
-pub struct InternalAttestation {
-    pub aggregation_bits: Bitlist<2048>, //TODO: the size of bit list is supposed to be maxValidatorsPerCommittee. Mainnet contains 2048
+pub struct InternalAttestation<const N: usize> {
+    pub aggregation_bits: Bitlist<N>,
     pub data: InternalAttestationData,
     pub signature: Vector<u8, 96>,
 }
 
-impl TryFrom<&Attestation> for InternalAttestation {
+impl<const N: usize> InternalAttestation<N> {
+    /// Attestation of at most `max_validators_per_committee` attesters, which may be less than `N`.
+    pub fn new(value: &Attestation, max_validators_per_committee: usize) -> Result<Self> {
+        Ok(Self {
+            aggregation_bits: bitlist(
+                &value.aggregation_bits,
+                max_validators_per_committee,
+                "aggregation_bits",
+            )?,
+            data: InternalAttestationData::try_from(&value.data)?,
+            signature: vector(value.signature.clone(), "signature")?,
+        })
+    }
+}
+
+impl<const N: usize> TryFrom<&Attestation> for InternalAttestation<N> {
     type Error = anyhow::Error;
 
     fn try_from(value: &Attestation) -> Result<Self, Self::Error> {
-        let aggregation_bits: Bitlist<2048> = Bitlist::try_from(value.aggregation_bits.as_slice())?;
-        let data = InternalAttestationData::try_from(&value.data)?;
-        let signature = Vector::<u8, 96>::try_from(value.signature.clone())
-            .map_err(|_| anyhow::anyhow!("Error converting signature bytes to ssz Vector"))?;
+        Self::new(value, N)
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct InternalAggregateAndProof<const N: usize> {
+    pub aggregator_index: u64,
+    pub aggregate: InternalAttestation<N>,
+    pub selection_proof: Vector<u8, 96>,
+}
 
+impl<const N: usize> InternalAggregateAndProof<N> {
+    pub fn new(value: &AggregateAndProof, max_validators_per_committee: usize) -> Result<Self> {
         Ok(Self {
-            aggregation_bits,
-            data,
-            signature,
+            aggregator_index: value.aggregator_index,
+            aggregate: InternalAttestation::new(&value.aggregate, max_validators_per_committee)?,
+            selection_proof: vector(value.selection_proof.clone(), "selection_proof")?,
         })
     }
 }
 
+impl<const N: usize> SigningRoot for InternalAggregateAndProof<N> {
+    fn compute_signing_root(&mut self, domain: &Hash256) -> Result<Hash256> {
+        let root = InternalSigningData {
+            object_root: self.hash_tree_root()?,
+            domain: *domain.as_fixed_bytes(),
+        }
+        .hash_tree_root()?;
+        Ok(Hash256::from_slice(root.as_ref()))
+    }
+}
+
+/// Electra attestation (EIP-7549), aggregating the committees of a slot flagged in
+/// `committee_bits`.
 #[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
-pub struct InternalAggregateAndProof {
-    pub aggregator_index: u64,
-    pub aggregate: InternalAttestation,
-    pub selection_proof: Vector<u8, 96>,
+pub struct InternalAttestationElectra<
+    const MAX_AGGREGATION_BITS: usize,
+    const MAX_COMMITTEES_PER_SLOT: usize,
+> {
+    pub aggregation_bits: Bitlist<MAX_AGGREGATION_BITS>,
+    pub data: InternalAttestationData,
+    pub signature: Vector<u8, 96>,
+    pub committee_bits: Bitvector<MAX_COMMITTEES_PER_SLOT>,
 }
 
-impl TryFrom<&AggregateAndProof> for InternalAggregateAndProof {
-    type Error = anyhow::Error;
+impl<const MAX_AGGREGATION_BITS: usize, const MAX_COMMITTEES_PER_SLOT: usize>
+    InternalAttestationElectra<MAX_AGGREGATION_BITS, MAX_COMMITTEES_PER_SLOT>
+{
+    pub fn new(
+        value: &AttestationElectra,
+        max_aggregation_bits: usize,
+        max_committees_per_slot: usize,
+    ) -> Result<Self> {
+        Ok(Self {
+            aggregation_bits: bitlist(
+                &value.aggregation_bits,
+                max_aggregation_bits,
+                "aggregation_bits",
+            )?,
+            data: InternalAttestationData::try_from(&value.data)?,
+            signature: vector(value.signature.clone(), "signature")?,
+            committee_bits: bitvector(
+                &value.committee_bits,
+                max_committees_per_slot,
+                "committee_bits",
+            )?,
+        })
+    }
+}
 
-    fn try_from(value: &AggregateAndProof) -> Result<Self, Self::Error> {
-        let aggregator_index = value.aggregator_index;
-        let aggregate = InternalAttestation::try_from(&value.aggregate)?;
-        let selection_proof = Vector::<u8, 96>::try_from(value.selection_proof.clone())
-            .map_err(|_| anyhow::anyhow!("Error converting selection_proof bytes to ssz Vector"))?;
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct InternalAggregateAndProofElectra<
+    const MAX_AGGREGATION_BITS: usize,
+    const MAX_COMMITTEES_PER_SLOT: usize,
+> {
+    pub aggregator_index: u64,
+    pub aggregate: InternalAttestationElectra<MAX_AGGREGATION_BITS, MAX_COMMITTEES_PER_SLOT>,
+    pub selection_proof: Vector<u8, 96>,
+}
 
+impl<const MAX_AGGREGATION_BITS: usize, const MAX_COMMITTEES_PER_SLOT: usize>
+    InternalAggregateAndProofElectra<MAX_AGGREGATION_BITS, MAX_COMMITTEES_PER_SLOT>
+{
+    pub fn new(
+        value: &AggregateAndProofElectra,
+        max_aggregation_bits: usize,
+        max_committees_per_slot: usize,
+    ) -> Result<Self> {
         Ok(Self {
-            aggregator_index,
-            aggregate,
-            selection_proof,
+            aggregator_index: value.aggregator_index,
+            aggregate: InternalAttestationElectra::new(
+                &value.aggregate,
+                max_aggregation_bits,
+                max_committees_per_slot,
+            )?,
+            selection_proof: vector(value.selection_proof.clone(), "selection_proof")?,
         })
     }
 }
 
-impl SigningRoot for InternalAggregateAndProof {
+impl<const MAX_AGGREGATION_BITS: usize, const MAX_COMMITTEES_PER_SLOT: usize> SigningRoot
+    for InternalAggregateAndProofElectra<MAX_AGGREGATION_BITS, MAX_COMMITTEES_PER_SLOT>
+{
     fn compute_signing_root(&mut self, domain: &Hash256) -> Result<Hash256> {
         let root = InternalSigningData {
             object_root: self.hash_tree_root()?,
@@ -248,18 +333,27 @@ impl Domain for ForkInfo {
             self.fork.current_version
         };
 
-        let mut fork_data = InternalForkData {
-            current_version: fork_version,
-            genesis_validators_root: *self.genesis_validators_root.as_fixed_bytes(),
-        };
-
-        let fork_data_root = fork_data.hash_tree_root()?;
-        let domain_root = [&domain_type.value(), &fork_data_root.as_ref()[..28]].concat();
-
-        Ok(Hash256::from_slice(&domain_root))
+        compute_domain(domain_type, fork_version, &self.genesis_validators_root)
     }
 }
 
+/// Domain of `domain_type` in the fork `fork_version` of the network of `genesis_validators_root`.
+pub fn compute_domain(
+    domain_type: &DomainType,
+    fork_version: [u8; 4],
+    genesis_validators_root: &Hash256,
+) -> Result<Hash256> {
+    let mut fork_data = InternalForkData {
+        current_version: fork_version,
+        genesis_validators_root: *genesis_validators_root.as_fixed_bytes(),
+    };
+
+    let fork_data_root = fork_data.hash_tree_root()?;
+    let domain_root = [&domain_type.value(), &fork_data_root.as_ref()[..28]].concat();
+
+    Ok(Hash256::from_slice(&domain_root))
+}
+
 impl DepositMessage {
     pub fn compute_domain(&self) -> Result<Hash256> {
         let domain_type = DomainType::Deposit;
@@ -384,23 +478,15 @@ pub struct InternalSyncCommitteeContribution<const N: usize> {
     pub signature: Vector<u8, 96>,
 }
 
-impl<const N: usize> TryFrom<&SyncCommitteeContribution> for InternalSyncCommitteeContribution<N> {
-    type Error = anyhow::Error;
-
-    fn try_from(value: &SyncCommitteeContribution) -> Result<Self, Self::Error> {
-        let slot = value.slot;
-        let beacon_block_root = *value.beacon_block_root.as_fixed_bytes();
-        let subcommittee_index = value.subcommittee_index;
-        let aggregation_bits = Bitvector::try_from(value.aggregation_bits.as_slice())?;
-        let signature = Vector::<u8, 96>::try_from(value.signature.clone())
-            .map_err(|_| anyhow::anyhow!("Error converting signature bytes to ssz Vector"))?;
-
+impl<const N: usize> InternalSyncCommitteeContribution<N> {
+    /// Contribution of a sync subcommittee of `size` members, which may be less than `N`.
+    pub fn new(value: &SyncCommitteeContribution, size: usize) -> Result<Self> {
         Ok(Self {
-            slot,
-            beacon_block_root,
-            subcommittee_index,
-            aggregation_bits,
-            signature,
+            slot: value.slot,
+            beacon_block_root: *value.beacon_block_root.as_fixed_bytes(),
+            subcommittee_index: value.subcommittee_index,
+            aggregation_bits: bitvector(&value.aggregation_bits, size, "aggregation_bits")?,
+            signature: vector(value.signature.clone(), "signature")?,
         })
     }
 }
@@ -412,25 +498,862 @@ pub struct InternalContributionAndProof<const N: usize> {
     pub selection_proof: Vector<u8, 96>,
 }
 
-impl<const N: usize> TryFrom<&ContributionAndProof> for InternalContributionAndProof<N> {
+impl<const N: usize> InternalContributionAndProof<N> {
+    pub fn new(value: &ContributionAndProof, size: usize) -> Result<Self> {
+        Ok(Self {
+            aggregator_index: value.aggregator_index,
+            contribution: InternalSyncCommitteeContribution::new(&value.contribution, size)?,
+            selection_proof: vector(value.selection_proof.clone(), "selection_proof")?,
+        })
+    }
+    pub fn compute_signing_root(&mut self, domain: &Hash256) -> Result<Hash256> {
+        let root = InternalSigningData {
+            object_root: self.hash_tree_root()?,
+            domain: *domain.as_fixed_bytes(),
+        }
+        .hash_tree_root()?;
+        Ok(Hash256::from_slice(root.as_ref()))
+    }
+}
+
+fn vector<T: SimpleSerialize, const N: usize>(values: Vec<T>, name: &str) -> Result<Vector<T, N>> {
+    Vector::try_from(values)
+        .map_err(|_| anyhow::anyhow!("Error converting {} to ssz Vector of {}", name, N))
+}
+
+fn list<T: SimpleSerialize, const N: usize>(values: Vec<T>, name: &str) -> Result<List<T, N>> {
+    List::try_from(values)
+        .map_err(|_| anyhow::anyhow!("Error converting {} to ssz List of at most {}", name, N))
+}
+
+/// Bitlist of at most `limit` bits held in a `Bitlist<N>` of the same merkle depth, `N >= limit`.
+fn bitlist<const N: usize>(value: &[u8], limit: usize, name: &str) -> Result<Bitlist<N>> {
+    let bitlist = Bitlist::<N>::try_from(value)
+        .map_err(|e| anyhow::anyhow!("Error converting {} to ssz Bitlist: {}", name, e))?;
+    if bitlist.len() > limit {
+        anyhow::bail!("{} has {} bits, more than {}", name, bitlist.len(), limit);
+    }
+    Ok(bitlist)
+}
+
+/// Bitvector of `size` bits held, zero padded, in a `Bitvector<N>` of the same merkle depth,
+/// `N >= size`.
+fn bitvector<const N: usize>(value: &[u8], size: usize, name: &str) -> Result<Bitvector<N>> {
+    let length = (size + 7) / 8;
+    if value.len() != length {
+        anyhow::bail!(
+            "Error converting {} to ssz Bitvector of {} bits, expected {} bytes",
+            name,
+            size,
+            length
+        );
+    }
+    if size % 8 != 0 && value[length - 1] >> (size % 8) != 0 {
+        anyhow::bail!("{} has bits set beyond {}", name, size);
+    }
+    let mut bytes = value.to_vec();
+    bytes.resize((N + 7) / 8, 0);
+    Bitvector::try_from(bytes.as_slice())
+        .map_err(|e| anyhow::anyhow!("Error converting {} to ssz Bitvector: {}", name, e))
+}
+
+fn list_of<'a, S, T, const N: usize>(values: &'a [S], name: &str) -> Result<List<T, N>>
+where
+    T: SimpleSerialize + TryFrom<&'a S, Error = anyhow::Error>,
+{
+    let values = values.iter().map(T::try_from).collect::<Result<Vec<_>>>()?;
+    list(values, name)
+}
+
+fn required<'a, T>(value: &'a Option<T>, name: &str) -> Result<&'a T> {
+    value
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("Missing {}", name))
+}
+
+/// Hash tree root of the ssz container converted from `value`.
+pub fn hash_tree_root<'a, S, T>(value: &'a S) -> Result<Hash256>
+where
+    T: SimpleSerialize + TryFrom<&'a S, Error = anyhow::Error>,
+{
+    let root = T::try_from(value)?.hash_tree_root()?;
+    Ok(Hash256::from_slice(root.as_ref()))
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct InternalSignedBeaconBlockHeader {
+    pub message: InternalBeaconBlockHeader,
+    pub signature: Vector<u8, 96>,
+}
+
+impl TryFrom<&SignedBeaconBlockHeader> for InternalSignedBeaconBlockHeader {
     type Error = anyhow::Error;
 
-    fn try_from(value: &ContributionAndProof) -> Result<Self, Self::Error> {
-        let aggregator_index = value.aggregator_index;
-        let contribution = InternalSyncCommitteeContribution::try_from(&value.contribution)?;
-        let selection_proof = Vector::<u8, 96>::try_from(value.selection_proof.clone())
-            .map_err(|_| anyhow::anyhow!("Error converting selection_proof bytes to ssz Vector"))?;
+    fn try_from(value: &SignedBeaconBlockHeader) -> Result<Self, Self::Error> {
+        Ok(Self {
+            message: InternalBeaconBlockHeader::try_from(&value.message)?,
+            signature: vector(value.signature.clone(), "signature")?,
+        })
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct InternalProposerSlashing {
+    pub signed_header_1: InternalSignedBeaconBlockHeader,
+    pub signed_header_2: InternalSignedBeaconBlockHeader,
+}
+
+impl TryFrom<&ProposerSlashing> for InternalProposerSlashing {
+    type Error = anyhow::Error;
 
+    fn try_from(value: &ProposerSlashing) -> Result<Self, Self::Error> {
         Ok(Self {
-            aggregator_index,
-            contribution,
-            selection_proof,
+            signed_header_1: InternalSignedBeaconBlockHeader::try_from(&value.signed_header_1)?,
+            signed_header_2: InternalSignedBeaconBlockHeader::try_from(&value.signed_header_2)?,
         })
     }
 }
 
-impl<const N: usize> InternalContributionAndProof<N> {
-    pub fn compute_signing_root(&mut self, domain: &Hash256) -> Result<Hash256> {
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct InternalIndexedAttestation {
+    pub attesting_indices: List<u64, MAX_VALIDATORS_PER_COMMITTEE>,
+    pub data: InternalAttestationData,
+    pub signature: Vector<u8, 96>,
+}
+
+impl TryFrom<&IndexedAttestation> for InternalIndexedAttestation {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &IndexedAttestation) -> Result<Self, Self::Error> {
+        Ok(Self {
+            attesting_indices: list(value.attesting_indices.clone(), "attesting_indices")?,
+            data: InternalAttestationData::try_from(&value.data)?,
+            signature: vector(value.signature.clone(), "signature")?,
+        })
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct InternalAttesterSlashing {
+    pub attestation_1: InternalIndexedAttestation,
+    pub attestation_2: InternalIndexedAttestation,
+}
+
+impl TryFrom<&AttesterSlashing> for InternalAttesterSlashing {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &AttesterSlashing) -> Result<Self, Self::Error> {
+        Ok(Self {
+            attestation_1: InternalIndexedAttestation::try_from(&value.attestation_1)?,
+            attestation_2: InternalIndexedAttestation::try_from(&value.attestation_2)?,
+        })
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct InternalEth1Data {
+    pub deposit_root: [u8; 32],
+    pub deposit_count: u64,
+    pub block_hash: [u8; 32],
+}
+
+impl TryFrom<&Eth1Data> for InternalEth1Data {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &Eth1Data) -> Result<Self, Self::Error> {
+        Ok(Self {
+            deposit_root: *value.deposit_root.as_fixed_bytes(),
+            deposit_count: value.deposit_count,
+            block_hash: *value.block_hash.as_fixed_bytes(),
+        })
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct InternalDepositData {
+    pub pubkey: Vector<u8, 48>,
+    pub withdrawal_credentials: [u8; 32],
+    pub amount: u64,
+    pub signature: Vector<u8, 96>,
+}
+
+impl TryFrom<&DepositData> for InternalDepositData {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &DepositData) -> Result<Self, Self::Error> {
+        Ok(Self {
+            pubkey: vector(value.pubkey.clone(), "pubkey")?,
+            withdrawal_credentials: *value.withdrawal_credentials.as_fixed_bytes(),
+            amount: value.amount,
+            signature: vector(value.signature.clone(), "signature")?,
+        })
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct InternalDeposit {
+    pub proof: Vector<[u8; 32], 33>, // DEPOSIT_CONTRACT_TREE_DEPTH + 1
+    pub data: InternalDepositData,
+}
+
+impl TryFrom<&Deposit> for InternalDeposit {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &Deposit) -> Result<Self, Self::Error> {
+        let proof = value
+            .proof
+            .iter()
+            .map(|node| node.to_fixed_bytes())
+            .collect();
+        Ok(Self {
+            proof: vector(proof, "proof")?,
+            data: InternalDepositData::try_from(&value.data)?,
+        })
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct InternalSignedVoluntaryExit {
+    pub message: InternalVoluntaryExit,
+    pub signature: Vector<u8, 96>,
+}
+
+impl TryFrom<&SignedVoluntaryExit> for InternalSignedVoluntaryExit {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &SignedVoluntaryExit) -> Result<Self, Self::Error> {
+        Ok(Self {
+            message: InternalVoluntaryExit::try_from(&value.message)?,
+            signature: vector(value.signature.clone(), "signature")?,
+        })
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct InternalSyncAggregate<const SYNC_COMMITTEE_SIZE: usize> {
+    pub sync_committee_bits: Bitvector<SYNC_COMMITTEE_SIZE>,
+    pub sync_committee_signature: Vector<u8, 96>,
+}
+
+impl<const SYNC_COMMITTEE_SIZE: usize> TryFrom<&SyncAggregate>
+    for InternalSyncAggregate<SYNC_COMMITTEE_SIZE>
+{
+    type Error = anyhow::Error;
+
+    fn try_from(value: &SyncAggregate) -> Result<Self, Self::Error> {
+        Ok(Self {
+            sync_committee_bits: Bitvector::try_from(value.sync_committee_bits.as_slice())?,
+            sync_committee_signature: vector(
+                value.sync_committee_signature.clone(),
+                "sync_committee_signature",
+            )?,
+        })
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct InternalWithdrawal {
+    pub index: u64,
+    pub validator_index: u64,
+    pub address: [u8; 20],
+    pub amount: u64,
+}
+
+impl TryFrom<&Withdrawal> for InternalWithdrawal {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &Withdrawal) -> Result<Self, Self::Error> {
+        Ok(Self {
+            index: value.index,
+            validator_index: value.validator_index,
+            address: value
+                .address
+                .as_slice()
+                .try_into()
+                .map_err(|_| anyhow::anyhow!("Error converting address to 20 bytes"))?,
+            amount: value.amount,
+        })
+    }
+}
+
+type Transaction = List<u8, MAX_BYTES_PER_TRANSACTION>;
+
+fn root(mut value: impl SimpleSerialize) -> Result<[u8; 32]> {
+    let root = value.hash_tree_root()?;
+    Ok(root.as_ref().try_into()?)
+}
+
+fn address(value: &[u8], name: &str) -> Result<[u8; 20]> {
+    value
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Error converting {} to 20 bytes", name))
+}
+
+fn base_fee_per_gas(value: &crate::types::U256) -> ssz_rs::U256 {
+    let mut bytes = [0; 32];
+    value.to_little_endian(&mut bytes);
+    ssz_rs::U256::from_bytes_le(bytes)
+}
+
+/// The execution payload of a body, or the header of a blinded body. A payload and its header
+/// have the same hash tree root, so bodies are hashed with the header.
+fn execution_payload_header<T>(
+    value: &BeaconBlockBody,
+    from_payload: impl FnOnce(&ExecutionPayload) -> Result<T>,
+) -> Result<T>
+where
+    T: for<'a> TryFrom<&'a ExecutionPayloadHeader, Error = anyhow::Error>,
+{
+    match (&value.execution_payload, &value.execution_payload_header) {
+        (Some(execution_payload), None) => from_payload(execution_payload),
+        (None, Some(execution_payload_header)) => T::try_from(execution_payload_header),
+        _ => anyhow::bail!("Expected one of execution_payload and execution_payload_header"),
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct InternalExecutionPayloadHeaderBellatrix {
+    pub parent_hash: [u8; 32],
+    pub fee_recipient: [u8; 20],
+    pub state_root: [u8; 32],
+    pub receipts_root: [u8; 32],
+    pub logs_bloom: Vector<u8, BYTES_PER_LOGS_BLOOM>,
+    pub prev_randao: [u8; 32],
+    pub block_number: u64,
+    pub gas_limit: u64,
+    pub gas_used: u64,
+    pub timestamp: u64,
+    pub extra_data: List<u8, MAX_EXTRA_DATA_BYTES>,
+    pub base_fee_per_gas: ssz_rs::U256,
+    pub block_hash: [u8; 32],
+    pub transactions_root: [u8; 32],
+}
+
+impl TryFrom<&ExecutionPayload> for InternalExecutionPayloadHeaderBellatrix {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &ExecutionPayload) -> Result<Self, Self::Error> {
+        let transactions = value
+            .transactions
+            .iter()
+            .map(|transaction| list(transaction.clone(), "transaction"))
+            .collect::<Result<Vec<Transaction>>>()?;
+        let transactions: List<Transaction, MAX_TRANSACTIONS_PER_PAYLOAD> =
+            list(transactions, "transactions")?;
+
+        Ok(Self {
+            parent_hash: *value.parent_hash.as_fixed_bytes(),
+            fee_recipient: address(&value.fee_recipient, "fee_recipient")?,
+            state_root: *value.state_root.as_fixed_bytes(),
+            receipts_root: *value.receipts_root.as_fixed_bytes(),
+            logs_bloom: vector(value.logs_bloom.clone(), "logs_bloom")?,
+            prev_randao: *value.prev_randao.as_fixed_bytes(),
+            block_number: value.block_number,
+            gas_limit: value.gas_limit,
+            gas_used: value.gas_used,
+            timestamp: value.timestamp,
+            extra_data: list(value.extra_data.clone(), "extra_data")?,
+            base_fee_per_gas: base_fee_per_gas(&value.base_fee_per_gas),
+            block_hash: *value.block_hash.as_fixed_bytes(),
+            transactions_root: root(transactions)?,
+        })
+    }
+}
+
+impl TryFrom<&ExecutionPayloadHeader> for InternalExecutionPayloadHeaderBellatrix {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &ExecutionPayloadHeader) -> Result<Self, Self::Error> {
+        Ok(Self {
+            parent_hash: *value.parent_hash.as_fixed_bytes(),
+            fee_recipient: address(&value.fee_recipient, "fee_recipient")?,
+            state_root: *value.state_root.as_fixed_bytes(),
+            receipts_root: *value.receipts_root.as_fixed_bytes(),
+            logs_bloom: vector(value.logs_bloom.clone(), "logs_bloom")?,
+            prev_randao: *value.prev_randao.as_fixed_bytes(),
+            block_number: value.block_number,
+            gas_limit: value.gas_limit,
+            gas_used: value.gas_used,
+            timestamp: value.timestamp,
+            extra_data: list(value.extra_data.clone(), "extra_data")?,
+            base_fee_per_gas: base_fee_per_gas(&value.base_fee_per_gas),
+            block_hash: *value.block_hash.as_fixed_bytes(),
+            transactions_root: *value.transactions_root.as_fixed_bytes(),
+        })
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct InternalExecutionPayloadHeaderCapella {
+    pub parent_hash: [u8; 32],
+    pub fee_recipient: [u8; 20],
+    pub state_root: [u8; 32],
+    pub receipts_root: [u8; 32],
+    pub logs_bloom: Vector<u8, BYTES_PER_LOGS_BLOOM>,
+    pub prev_randao: [u8; 32],
+    pub block_number: u64,
+    pub gas_limit: u64,
+    pub gas_used: u64,
+    pub timestamp: u64,
+    pub extra_data: List<u8, MAX_EXTRA_DATA_BYTES>,
+    pub base_fee_per_gas: ssz_rs::U256,
+    pub block_hash: [u8; 32],
+    pub transactions_root: [u8; 32],
+    pub withdrawals_root: [u8; 32],
+}
+
+impl InternalExecutionPayloadHeaderCapella {
+    fn new(header: InternalExecutionPayloadHeaderBellatrix, withdrawals_root: [u8; 32]) -> Self {
+        Self {
+            parent_hash: header.parent_hash,
+            fee_recipient: header.fee_recipient,
+            state_root: header.state_root,
+            receipts_root: header.receipts_root,
+            logs_bloom: header.logs_bloom,
+            prev_randao: header.prev_randao,
+            block_number: header.block_number,
+            gas_limit: header.gas_limit,
+            gas_used: header.gas_used,
+            timestamp: header.timestamp,
+            extra_data: header.extra_data,
+            base_fee_per_gas: header.base_fee_per_gas,
+            block_hash: header.block_hash,
+            transactions_root: header.transactions_root,
+            withdrawals_root,
+        }
+    }
+
+    /// Header of a payload, with the root of its withdrawals.
+    fn from_payload<const MAX_WITHDRAWALS_PER_PAYLOAD: usize>(
+        value: &ExecutionPayload,
+    ) -> Result<Self> {
+        let withdrawals: List<InternalWithdrawal, MAX_WITHDRAWALS_PER_PAYLOAD> =
+            list_of(required(&value.withdrawals, "withdrawals")?, "withdrawals")?;
+        Ok(Self::new(
+            InternalExecutionPayloadHeaderBellatrix::try_from(value)?,
+            root(withdrawals)?,
+        ))
+    }
+}
+
+impl TryFrom<&ExecutionPayloadHeader> for InternalExecutionPayloadHeaderCapella {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &ExecutionPayloadHeader) -> Result<Self, Self::Error> {
+        Ok(Self::new(
+            InternalExecutionPayloadHeaderBellatrix::try_from(value)?,
+            *required(&value.withdrawals_root, "withdrawals_root")?.as_fixed_bytes(),
+        ))
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct InternalExecutionPayloadHeaderDeneb {
+    pub parent_hash: [u8; 32],
+    pub fee_recipient: [u8; 20],
+    pub state_root: [u8; 32],
+    pub receipts_root: [u8; 32],
+    pub logs_bloom: Vector<u8, BYTES_PER_LOGS_BLOOM>,
+    pub prev_randao: [u8; 32],
+    pub block_number: u64,
+    pub gas_limit: u64,
+    pub gas_used: u64,
+    pub timestamp: u64,
+    pub extra_data: List<u8, MAX_EXTRA_DATA_BYTES>,
+    pub base_fee_per_gas: ssz_rs::U256,
+    pub block_hash: [u8; 32],
+    pub transactions_root: [u8; 32],
+    pub withdrawals_root: [u8; 32],
+    pub blob_gas_used: u64,
+    pub excess_blob_gas: u64,
+}
+
+impl InternalExecutionPayloadHeaderDeneb {
+    fn new(
+        header: InternalExecutionPayloadHeaderCapella,
+        blob_gas_used: &Option<u64>,
+        excess_blob_gas: &Option<u64>,
+    ) -> Result<Self> {
+        Ok(Self {
+            parent_hash: header.parent_hash,
+            fee_recipient: header.fee_recipient,
+            state_root: header.state_root,
+            receipts_root: header.receipts_root,
+            logs_bloom: header.logs_bloom,
+            prev_randao: header.prev_randao,
+            block_number: header.block_number,
+            gas_limit: header.gas_limit,
+            gas_used: header.gas_used,
+            timestamp: header.timestamp,
+            extra_data: header.extra_data,
+            base_fee_per_gas: header.base_fee_per_gas,
+            block_hash: header.block_hash,
+            transactions_root: header.transactions_root,
+            withdrawals_root: header.withdrawals_root,
+            blob_gas_used: *required(blob_gas_used, "blob_gas_used")?,
+            excess_blob_gas: *required(excess_blob_gas, "excess_blob_gas")?,
+        })
+    }
+
+    fn from_payload<const MAX_WITHDRAWALS_PER_PAYLOAD: usize>(
+        value: &ExecutionPayload,
+    ) -> Result<Self> {
+        Self::new(
+            InternalExecutionPayloadHeaderCapella::from_payload::<MAX_WITHDRAWALS_PER_PAYLOAD>(
+                value,
+            )?,
+            &value.blob_gas_used,
+            &value.excess_blob_gas,
+        )
+    }
+}
+
+impl TryFrom<&ExecutionPayloadHeader> for InternalExecutionPayloadHeaderDeneb {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &ExecutionPayloadHeader) -> Result<Self, Self::Error> {
+        Self::new(
+            InternalExecutionPayloadHeaderCapella::try_from(value)?,
+            &value.blob_gas_used,
+            &value.excess_blob_gas,
+        )
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct InternalBLSToExecutionChange {
+    pub validator_index: u64,
+    pub from_bls_pubkey: Vector<u8, 48>,
+    pub to_execution_address: [u8; 20],
+}
+
+impl TryFrom<&BLSToExecutionChange> for InternalBLSToExecutionChange {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &BLSToExecutionChange) -> Result<Self, Self::Error> {
+        Ok(Self {
+            validator_index: value.validator_index,
+            from_bls_pubkey: vector(value.from_bls_pubkey.clone(), "from_bls_pubkey")?,
+            to_execution_address: address(&value.to_execution_address, "to_execution_address")?,
+        })
+    }
+}
+
+impl SigningRoot for InternalBLSToExecutionChange {
+    fn compute_signing_root(&mut self, domain: &Hash256) -> Result<Hash256> {
+        let root = InternalSigningData {
+            object_root: self.hash_tree_root()?,
+            domain: *domain.as_fixed_bytes(),
+        }
+        .hash_tree_root()?;
+        Ok(Hash256::from_slice(root.as_ref()))
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct InternalSignedBLSToExecutionChange {
+    pub message: InternalBLSToExecutionChange,
+    pub signature: Vector<u8, 96>,
+}
+
+impl TryFrom<&SignedBLSToExecutionChange> for InternalSignedBLSToExecutionChange {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &SignedBLSToExecutionChange) -> Result<Self, Self::Error> {
+        Ok(Self {
+            message: InternalBLSToExecutionChange::try_from(&value.message)?,
+            signature: vector(value.signature.clone(), "signature")?,
+        })
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct InternalBeaconBlockBodyPhase0 {
+    pub randao_reveal: Vector<u8, 96>,
+    pub eth1_data: InternalEth1Data,
+    pub graffiti: [u8; 32],
+    pub proposer_slashings: List<InternalProposerSlashing, MAX_PROPOSER_SLASHINGS>,
+    pub attester_slashings: List<InternalAttesterSlashing, MAX_ATTESTER_SLASHINGS>,
+    pub attestations: List<InternalAttestation<MAX_VALIDATORS_PER_COMMITTEE>, MAX_ATTESTATIONS>,
+    pub deposits: List<InternalDeposit, MAX_DEPOSITS>,
+    pub voluntary_exits: List<InternalSignedVoluntaryExit, MAX_VOLUNTARY_EXITS>,
+}
+
+impl TryFrom<&BeaconBlockBody> for InternalBeaconBlockBodyPhase0 {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &BeaconBlockBody) -> Result<Self, Self::Error> {
+        Ok(Self {
+            randao_reveal: vector(value.randao_reveal.clone(), "randao_reveal")?,
+            eth1_data: InternalEth1Data::try_from(&value.eth1_data)?,
+            graffiti: *value.graffiti.as_fixed_bytes(),
+            proposer_slashings: list_of(&value.proposer_slashings, "proposer_slashings")?,
+            attester_slashings: list_of(&value.attester_slashings, "attester_slashings")?,
+            attestations: list_of(&value.attestations, "attestations")?,
+            deposits: list_of(&value.deposits, "deposits")?,
+            voluntary_exits: list_of(&value.voluntary_exits, "voluntary_exits")?,
+        })
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct InternalBeaconBlockBodyAltair<const SYNC_COMMITTEE_SIZE: usize> {
+    pub randao_reveal: Vector<u8, 96>,
+    pub eth1_data: InternalEth1Data,
+    pub graffiti: [u8; 32],
+    pub proposer_slashings: List<InternalProposerSlashing, MAX_PROPOSER_SLASHINGS>,
+    pub attester_slashings: List<InternalAttesterSlashing, MAX_ATTESTER_SLASHINGS>,
+    pub attestations: List<InternalAttestation<MAX_VALIDATORS_PER_COMMITTEE>, MAX_ATTESTATIONS>,
+    pub deposits: List<InternalDeposit, MAX_DEPOSITS>,
+    pub voluntary_exits: List<InternalSignedVoluntaryExit, MAX_VOLUNTARY_EXITS>,
+    pub sync_aggregate: InternalSyncAggregate<SYNC_COMMITTEE_SIZE>,
+}
+
+impl<const SYNC_COMMITTEE_SIZE: usize> TryFrom<&BeaconBlockBody>
+    for InternalBeaconBlockBodyAltair<SYNC_COMMITTEE_SIZE>
+{
+    type Error = anyhow::Error;
+
+    fn try_from(value: &BeaconBlockBody) -> Result<Self, Self::Error> {
+        let body = InternalBeaconBlockBodyPhase0::try_from(value)?;
+        Ok(Self {
+            randao_reveal: body.randao_reveal,
+            eth1_data: body.eth1_data,
+            graffiti: body.graffiti,
+            proposer_slashings: body.proposer_slashings,
+            attester_slashings: body.attester_slashings,
+            attestations: body.attestations,
+            deposits: body.deposits,
+            voluntary_exits: body.voluntary_exits,
+            sync_aggregate: InternalSyncAggregate::try_from(required(
+                &value.sync_aggregate,
+                "sync_aggregate",
+            )?)?,
+        })
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct InternalBeaconBlockBodyBellatrix<const SYNC_COMMITTEE_SIZE: usize> {
+    pub randao_reveal: Vector<u8, 96>,
+    pub eth1_data: InternalEth1Data,
+    pub graffiti: [u8; 32],
+    pub proposer_slashings: List<InternalProposerSlashing, MAX_PROPOSER_SLASHINGS>,
+    pub attester_slashings: List<InternalAttesterSlashing, MAX_ATTESTER_SLASHINGS>,
+    pub attestations: List<InternalAttestation<MAX_VALIDATORS_PER_COMMITTEE>, MAX_ATTESTATIONS>,
+    pub deposits: List<InternalDeposit, MAX_DEPOSITS>,
+    pub voluntary_exits: List<InternalSignedVoluntaryExit, MAX_VOLUNTARY_EXITS>,
+    pub sync_aggregate: InternalSyncAggregate<SYNC_COMMITTEE_SIZE>,
+    pub execution_payload_header: InternalExecutionPayloadHeaderBellatrix,
+}
+
+impl<const SYNC_COMMITTEE_SIZE: usize> TryFrom<&BeaconBlockBody>
+    for InternalBeaconBlockBodyBellatrix<SYNC_COMMITTEE_SIZE>
+{
+    type Error = anyhow::Error;
+
+    fn try_from(value: &BeaconBlockBody) -> Result<Self, Self::Error> {
+        let body = InternalBeaconBlockBodyAltair::<SYNC_COMMITTEE_SIZE>::try_from(value)?;
+        Ok(Self {
+            randao_reveal: body.randao_reveal,
+            eth1_data: body.eth1_data,
+            graffiti: body.graffiti,
+            proposer_slashings: body.proposer_slashings,
+            attester_slashings: body.attester_slashings,
+            attestations: body.attestations,
+            deposits: body.deposits,
+            voluntary_exits: body.voluntary_exits,
+            sync_aggregate: body.sync_aggregate,
+            execution_payload_header: execution_payload_header(value, |payload| {
+                InternalExecutionPayloadHeaderBellatrix::try_from(payload)
+            })?,
+        })
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct InternalBeaconBlockBodyCapella<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+> {
+    pub randao_reveal: Vector<u8, 96>,
+    pub eth1_data: InternalEth1Data,
+    pub graffiti: [u8; 32],
+    pub proposer_slashings: List<InternalProposerSlashing, MAX_PROPOSER_SLASHINGS>,
+    pub attester_slashings: List<InternalAttesterSlashing, MAX_ATTESTER_SLASHINGS>,
+    pub attestations: List<InternalAttestation<MAX_VALIDATORS_PER_COMMITTEE>, MAX_ATTESTATIONS>,
+    pub deposits: List<InternalDeposit, MAX_DEPOSITS>,
+    pub voluntary_exits: List<InternalSignedVoluntaryExit, MAX_VOLUNTARY_EXITS>,
+    pub sync_aggregate: InternalSyncAggregate<SYNC_COMMITTEE_SIZE>,
+    pub execution_payload_header: InternalExecutionPayloadHeaderCapella,
+    pub bls_to_execution_changes:
+        List<InternalSignedBLSToExecutionChange, MAX_BLS_TO_EXECUTION_CHANGES>,
+}
+
+impl<const SYNC_COMMITTEE_SIZE: usize, const MAX_WITHDRAWALS_PER_PAYLOAD: usize>
+    TryFrom<&BeaconBlockBody>
+    for InternalBeaconBlockBodyCapella<SYNC_COMMITTEE_SIZE, MAX_WITHDRAWALS_PER_PAYLOAD>
+{
+    type Error = anyhow::Error;
+
+    fn try_from(value: &BeaconBlockBody) -> Result<Self, Self::Error> {
+        let body = InternalBeaconBlockBodyAltair::<SYNC_COMMITTEE_SIZE>::try_from(value)?;
+        Ok(Self {
+            randao_reveal: body.randao_reveal,
+            eth1_data: body.eth1_data,
+            graffiti: body.graffiti,
+            proposer_slashings: body.proposer_slashings,
+            attester_slashings: body.attester_slashings,
+            attestations: body.attestations,
+            deposits: body.deposits,
+            voluntary_exits: body.voluntary_exits,
+            sync_aggregate: body.sync_aggregate,
+            execution_payload_header: execution_payload_header(
+                value,
+                InternalExecutionPayloadHeaderCapella::from_payload::<MAX_WITHDRAWALS_PER_PAYLOAD>,
+            )?,
+            bls_to_execution_changes: list_of(
+                required(&value.bls_to_execution_changes, "bls_to_execution_changes")?,
+                "bls_to_execution_changes",
+            )?,
+        })
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct InternalBeaconBlockBodyDeneb<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+> {
+    pub randao_reveal: Vector<u8, 96>,
+    pub eth1_data: InternalEth1Data,
+    pub graffiti: [u8; 32],
+    pub proposer_slashings: List<InternalProposerSlashing, MAX_PROPOSER_SLASHINGS>,
+    pub attester_slashings: List<InternalAttesterSlashing, MAX_ATTESTER_SLASHINGS>,
+    pub attestations: List<InternalAttestation<MAX_VALIDATORS_PER_COMMITTEE>, MAX_ATTESTATIONS>,
+    pub deposits: List<InternalDeposit, MAX_DEPOSITS>,
+    pub voluntary_exits: List<InternalSignedVoluntaryExit, MAX_VOLUNTARY_EXITS>,
+    pub sync_aggregate: InternalSyncAggregate<SYNC_COMMITTEE_SIZE>,
+    pub execution_payload_header: InternalExecutionPayloadHeaderDeneb,
+    pub bls_to_execution_changes:
+        List<InternalSignedBLSToExecutionChange, MAX_BLS_TO_EXECUTION_CHANGES>,
+    pub blob_kzg_commitments: List<Vector<u8, 48>, MAX_BLOB_COMMITMENTS_PER_BLOCK>,
+}
+
+impl<
+        const SYNC_COMMITTEE_SIZE: usize,
+        const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+        const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+    > TryFrom<&BeaconBlockBody>
+    for InternalBeaconBlockBodyDeneb<
+        SYNC_COMMITTEE_SIZE,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+    >
+{
+    type Error = anyhow::Error;
+
+    fn try_from(value: &BeaconBlockBody) -> Result<Self, Self::Error> {
+        let body = InternalBeaconBlockBodyAltair::<SYNC_COMMITTEE_SIZE>::try_from(value)?;
+        let blob_kzg_commitments = required(&value.blob_kzg_commitments, "blob_kzg_commitments")?
+            .iter()
+            .map(|commitment| vector(commitment.0.clone(), "blob_kzg_commitment"))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            randao_reveal: body.randao_reveal,
+            eth1_data: body.eth1_data,
+            graffiti: body.graffiti,
+            proposer_slashings: body.proposer_slashings,
+            attester_slashings: body.attester_slashings,
+            attestations: body.attestations,
+            deposits: body.deposits,
+            voluntary_exits: body.voluntary_exits,
+            sync_aggregate: body.sync_aggregate,
+            execution_payload_header: execution_payload_header(
+                value,
+                InternalExecutionPayloadHeaderDeneb::from_payload::<MAX_WITHDRAWALS_PER_PAYLOAD>,
+            )?,
+            bls_to_execution_changes: list_of(
+                required(&value.bls_to_execution_changes, "bls_to_execution_changes")?,
+                "bls_to_execution_changes",
+            )?,
+            blob_kzg_commitments: list(blob_kzg_commitments, "blob_kzg_commitments")?,
+        })
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct InternalBlobSidecar<const BYTES_PER_BLOB: usize> {
+    pub block_root: [u8; 32],
+    pub index: u64,
+    pub slot: u64,
+    pub block_parent_root: [u8; 32],
+    pub proposer_index: u64,
+    pub blob: Vector<u8, BYTES_PER_BLOB>,
+    pub kzg_commitment: Vector<u8, 48>,
+    pub kzg_proof: Vector<u8, 48>,
+}
+
+impl<const BYTES_PER_BLOB: usize> TryFrom<&BlobSidecar> for InternalBlobSidecar<BYTES_PER_BLOB> {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &BlobSidecar) -> Result<Self, Self::Error> {
+        Ok(Self {
+            block_root: *value.block_root.as_fixed_bytes(),
+            index: value.index,
+            slot: value.slot,
+            block_parent_root: *value.block_parent_root.as_fixed_bytes(),
+            proposer_index: value.proposer_index,
+            blob: vector(value.blob.clone(), "blob")?,
+            kzg_commitment: vector(value.kzg_commitment.clone(), "kzg_commitment")?,
+            kzg_proof: vector(value.kzg_proof.clone(), "kzg_proof")?,
+        })
+    }
+}
+
+impl<const BYTES_PER_BLOB: usize> SigningRoot for InternalBlobSidecar<BYTES_PER_BLOB> {
+    fn compute_signing_root(&mut self, domain: &Hash256) -> Result<Hash256> {
+        let root = InternalSigningData {
+            object_root: self.hash_tree_root()?,
+            domain: *domain.as_fixed_bytes(),
+        }
+        .hash_tree_root()?;
+        Ok(Hash256::from_slice(root.as_ref()))
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct InternalBlindedBlobSidecar {
+    pub block_root: [u8; 32],
+    pub index: u64,
+    pub slot: u64,
+    pub block_parent_root: [u8; 32],
+    pub proposer_index: u64,
+    pub blob_root: [u8; 32],
+    pub kzg_commitment: Vector<u8, 48>,
+    pub kzg_proof: Vector<u8, 48>,
+}
+
+impl TryFrom<&BlindedBlobSidecar> for InternalBlindedBlobSidecar {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &BlindedBlobSidecar) -> Result<Self, Self::Error> {
+        Ok(Self {
+            block_root: *value.block_root.as_fixed_bytes(),
+            index: value.index,
+            slot: value.slot,
+            block_parent_root: *value.block_parent_root.as_fixed_bytes(),
+            proposer_index: value.proposer_index,
+            blob_root: *value.blob_root.as_fixed_bytes(),
+            kzg_commitment: vector(value.kzg_commitment.clone(), "kzg_commitment")?,
+            kzg_proof: vector(value.kzg_proof.clone(), "kzg_proof")?,
+        })
+    }
+}
+
+impl SigningRoot for InternalBlindedBlobSidecar {
+    fn compute_signing_root(&mut self, domain: &Hash256) -> Result<Hash256> {
         let root = InternalSigningData {
             object_root: self.hash_tree_root()?,
             domain: *domain.as_fixed_bytes(),
@@ -8,15 +8,111 @@ pub mod types;
 use crate::internal::*;
 use crate::types::*;
 use anyhow::Result;
-use specs::{Spec, SYNC_COMMITTEE_CONT_SIZE_MAINNET, SYNC_COMMITTEE_CONT_SIZE_MIMIMAL};
+use specs::{
+    Spec, BYTES_PER_FIELD_ELEMENT, FIELD_ELEMENTS_PER_BLOB_MAINNET,
+    FIELD_ELEMENTS_PER_BLOB_MINIMAL, MAX_BLOB_COMMITMENTS_PER_BLOCK_MAINNET,
+    MAX_BLOB_COMMITMENTS_PER_BLOCK_MINIMAL, MAX_COMMITTEES_PER_SLOT_MAINNET,
+    MAX_COMMITTEES_PER_SLOT_MINIMAL, MAX_VALIDATORS_PER_COMMITTEE,
+    MAX_WITHDRAWALS_PER_PAYLOAD_MAINNET, MAX_WITHDRAWALS_PER_PAYLOAD_MINIMAL,
+    SYNC_COMMITTEE_CONT_SIZE_MAINNET, SYNC_COMMITTEE_CONT_SIZE_MIMIMAL,
+    SYNC_COMMITTEE_SIZE_MAINNET, SYNC_COMMITTEE_SIZE_MINIMAL,
+};
+
+const AGGREGATION_BITS_ELECTRA_MAINNET: usize =
+    MAX_VALIDATORS_PER_COMMITTEE * MAX_COMMITTEES_PER_SLOT_MAINNET;
+const AGGREGATION_BITS_ELECTRA_MINIMAL: usize =
+    MAX_VALIDATORS_PER_COMMITTEE * MAX_COMMITTEES_PER_SLOT_MINIMAL;
 
 pub struct SigningRootUtil<'a> {
     spec: &'a Spec,
+    validate_fork_info: bool,
+    genesis_validators_root: Option<Hash256>,
 }
 
 impl<'a> SigningRootUtil<'a> {
     pub fn new(spec: &'a Spec) -> Self {
-        SigningRootUtil { spec }
+        SigningRootUtil {
+            spec,
+            validate_fork_info: false,
+            genesis_validators_root: None,
+        }
+    }
+
+    /// Verify the `fork_info` of every request with `verify_fork_info`, the
+    /// `genesis_validators_root` of the network is checked when known.
+    pub fn with_fork_info_validation(mut self, genesis_validators_root: Option<Hash256>) -> Self {
+        self.validate_fork_info = true;
+        self.genesis_validators_root = genesis_validators_root;
+        self
+    }
+
+    /// Domain of `domain_type` at `epoch` computed from the fork schedule of the spec.
+    pub fn compute_domain(
+        &self,
+        domain_type: &DomainType,
+        epoch: u64,
+        genesis_validators_root: &Hash256,
+    ) -> Result<Hash256> {
+        compute_domain(
+            domain_type,
+            self.spec.fork_version_at_epoch(epoch),
+            genesis_validators_root,
+        )
+    }
+
+    /// Verify that `fork_info` is a fork of the schedule of the spec which is still current at
+    /// `epoch`, and that it belongs to the network of the known `genesis_validators_root`.
+    pub fn verify_fork_info(&self, fork_info: &ForkInfo, epoch: u64) -> Result<()> {
+        let fork = &fork_info.fork;
+        let scheduled = self.spec.fork_at_epoch(fork.epoch);
+        if (fork.previous_version, fork.current_version, fork.epoch)
+            != (
+                scheduled.previous_version,
+                scheduled.current_version,
+                scheduled.epoch,
+            )
+        {
+            return Err(SigningRootError::ForkInfoMismatch(format!(
+                "fork {:?} is not a fork of {}",
+                fork,
+                self.spec.config_name()
+            ))
+            .into());
+        }
+        if let Some(next_fork) = self.spec.next_fork(fork.epoch) {
+            if epoch >= next_fork.epoch {
+                return Err(SigningRootError::ForkInfoMismatch(format!(
+                    "epoch {} is past the {} fork at epoch {}",
+                    epoch, next_fork.name, next_fork.epoch
+                ))
+                .into());
+            }
+        }
+        match self.genesis_validators_root {
+            Some(genesis_validators_root)
+                if genesis_validators_root != fork_info.genesis_validators_root =>
+            {
+                Err(SigningRootError::ForkInfoMismatch(format!(
+                    "genesis_validators_root {:?} is not {:?}",
+                    fork_info.genesis_validators_root, genesis_validators_root
+                ))
+                .into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Domain of `domain_type` at `epoch` from `fork_info`, verified first when enabled.
+    fn domain(
+        &self,
+        fork_info: &ForkInfo,
+        domain_type: &DomainType,
+        epoch: u64,
+    ) -> Result<Hash256> {
+        if self.validate_fork_info {
+            self.verify_fork_info(fork_info, epoch)?;
+        }
+        fork_info.compute_domain(domain_type, epoch)
     }
 
     pub fn signing_root_for_sign_block_header(
@@ -25,17 +121,134 @@ impl<'a> SigningRootUtil<'a> {
         fork_info: &ForkInfo,
     ) -> Result<Hash256> {
         let epoch = self.spec.compute_epoch_at_slot(block_header.slot);
-        let domain = fork_info.compute_domain(&DomainType::BeaconProposer, epoch)?;
+        let domain = self.domain(fork_info, &DomainType::BeaconProposer, epoch)?;
 
         InternalBeaconBlockHeader::try_from(block_header)?.compute_signing_root(&domain)
     }
 
+    pub fn signing_root_for_sign_block(
+        &self,
+        version: ForkName,
+        block: &BeaconBlock,
+        fork_info: &ForkInfo,
+    ) -> Result<Hash256> {
+        self.signing_root_for_sign_block_header(&self.block_header(version, block)?, fork_info)
+    }
+
+    /// Header of a full beacon block, with the hash tree root of its body.
+    pub fn block_header(
+        &self,
+        version: ForkName,
+        block: &BeaconBlock,
+    ) -> Result<BeaconBlockHeader> {
+        Ok(BeaconBlockHeader {
+            slot: block.slot,
+            proposer_index: block.proposer_index,
+            parent_root: block.parent_root,
+            state_root: block.state_root,
+            body_root: self.body_root(version, &block.body)?,
+        })
+    }
+
+    /// Hash tree root of a beacon block body of the `version` fork, sized by the spec presets.
+    pub fn body_root(&self, version: ForkName, body: &BeaconBlockBody) -> Result<Hash256> {
+        check_body_fields(version, body)?;
+        self.spec.check_block_limits()?;
+
+        const MAINNET: (usize, usize, usize) = (
+            SYNC_COMMITTEE_SIZE_MAINNET,
+            MAX_WITHDRAWALS_PER_PAYLOAD_MAINNET,
+            MAX_BLOB_COMMITMENTS_PER_BLOCK_MAINNET,
+        );
+        const MINIMAL: (usize, usize, usize) = (
+            SYNC_COMMITTEE_SIZE_MINIMAL,
+            MAX_WITHDRAWALS_PER_PAYLOAD_MINIMAL,
+            MAX_BLOB_COMMITMENTS_PER_BLOCK_MINIMAL,
+        );
+        let preset = (
+            self.spec.sync_committee_size(),
+            self.spec.max_withdrawals_per_payload(),
+            self.spec.max_blob_commitments_per_block(),
+        );
+
+        match (version, preset) {
+            (ForkName::Phase0, _) => hash_tree_root::<_, InternalBeaconBlockBodyPhase0>(body),
+            (ForkName::Altair, MAINNET) => hash_tree_root::<
+                _,
+                InternalBeaconBlockBodyAltair<SYNC_COMMITTEE_SIZE_MAINNET>,
+            >(body),
+            (ForkName::Altair, MINIMAL) => hash_tree_root::<
+                _,
+                InternalBeaconBlockBodyAltair<SYNC_COMMITTEE_SIZE_MINIMAL>,
+            >(body),
+            (ForkName::Bellatrix, MAINNET) => hash_tree_root::<
+                _,
+                InternalBeaconBlockBodyBellatrix<SYNC_COMMITTEE_SIZE_MAINNET>,
+            >(body),
+            (ForkName::Bellatrix, MINIMAL) => hash_tree_root::<
+                _,
+                InternalBeaconBlockBodyBellatrix<SYNC_COMMITTEE_SIZE_MINIMAL>,
+            >(body),
+            (ForkName::Capella, MAINNET) => hash_tree_root::<
+                _,
+                InternalBeaconBlockBodyCapella<
+                    SYNC_COMMITTEE_SIZE_MAINNET,
+                    MAX_WITHDRAWALS_PER_PAYLOAD_MAINNET,
+                >,
+            >(body),
+            (ForkName::Capella, MINIMAL) => hash_tree_root::<
+                _,
+                InternalBeaconBlockBodyCapella<
+                    SYNC_COMMITTEE_SIZE_MINIMAL,
+                    MAX_WITHDRAWALS_PER_PAYLOAD_MINIMAL,
+                >,
+            >(body),
+            (ForkName::Deneb, MAINNET) => hash_tree_root::<
+                _,
+                InternalBeaconBlockBodyDeneb<
+                    SYNC_COMMITTEE_SIZE_MAINNET,
+                    MAX_WITHDRAWALS_PER_PAYLOAD_MAINNET,
+                    MAX_BLOB_COMMITMENTS_PER_BLOCK_MAINNET,
+                >,
+            >(body),
+            (ForkName::Deneb, MINIMAL) => hash_tree_root::<
+                _,
+                InternalBeaconBlockBodyDeneb<
+                    SYNC_COMMITTEE_SIZE_MINIMAL,
+                    MAX_WITHDRAWALS_PER_PAYLOAD_MINIMAL,
+                    MAX_BLOB_COMMITMENTS_PER_BLOCK_MINIMAL,
+                >,
+            >(body),
+            // checked like a Deneb body first, but execution_requests and the EIP-7549
+            // attestations are not hashed yet
+            (ForkName::Electra, _) => anyhow::bail!(
+                "Electra block bodies are not supported, send the block_header instead"
+            ),
+            (_, (sync_committee_size, max_withdrawals, max_blob_commitments)) => anyhow::bail!(
+                "Unsupported preset SYNC_COMMITTEE_SIZE {}, MAX_WITHDRAWALS_PER_PAYLOAD {}, \
+                 MAX_BLOB_COMMITMENTS_PER_BLOCK {}",
+                sync_committee_size,
+                max_withdrawals,
+                max_blob_commitments
+            ),
+        }
+    }
+
+    /// Header of the block of a `BLOCK_V2` request, computed when the full block is sent.
+    pub fn block_request_header(&self, block_request: &BlockRequest) -> Result<BeaconBlockHeader> {
+        match (&block_request.block, &block_request.block_header) {
+            (Some(block), None) => self.block_header(block_request.version, block),
+            (None, Some(block_header)) => Ok(block_header.clone()),
+            _ => anyhow::bail!("beacon_block requires exactly one of block and block_header"),
+        }
+    }
+
     pub fn signing_root_for_randao_reveal(
         &self,
         randao_reveal: &RandaoReveal,
         fork_info: &ForkInfo,
     ) -> Result<Hash256> {
-        let domain = fork_info.compute_domain(&DomainType::Randao, randao_reveal.epoch)?;
+        let domain = self.domain(fork_info, &DomainType::Randao, randao_reveal.epoch)?;
 
         SszU64(randao_reveal.epoch).compute_signing_root(&domain)
     }
@@ -45,7 +258,7 @@ impl<'a> SigningRootUtil<'a> {
         voluntary_exit: &VoluntaryExit,
         fork_info: &ForkInfo,
     ) -> Result<Hash256> {
-        let domain = fork_info.compute_domain(&DomainType::VoluntaryExit, voluntary_exit.epoch)?;
+        let domain = self.domain(fork_info, &DomainType::VoluntaryExit, voluntary_exit.epoch)?;
 
         InternalVoluntaryExit::try_from(voluntary_exit)?.compute_signing_root(&domain)
     }
@@ -55,8 +268,11 @@ impl<'a> SigningRootUtil<'a> {
         attestation_data: &AttestationData,
         fork_info: &ForkInfo,
     ) -> Result<Hash256> {
-        let domain =
-            fork_info.compute_domain(&DomainType::BeaconAttester, attestation_data.target.epoch)?;
+        let domain = self.domain(
+            fork_info,
+            &DomainType::BeaconAttester,
+            attestation_data.target.epoch,
+        )?;
 
         InternalAttestationData::try_from(attestation_data)?.compute_signing_root(&domain)
     }
@@ -67,7 +283,7 @@ impl<'a> SigningRootUtil<'a> {
         fork_info: &ForkInfo,
     ) -> Result<Hash256> {
         let epoch = self.spec.compute_epoch_at_slot(aggregation_slot.slot);
-        let domain = fork_info.compute_domain(&DomainType::SelectionProof, epoch)?;
+        let domain = self.domain(fork_info, &DomainType::SelectionProof, epoch)?;
 
         SszU64(aggregation_slot.slot).compute_signing_root(&domain)
     }
@@ -81,9 +297,107 @@ impl<'a> SigningRootUtil<'a> {
             .spec
             .compute_epoch_at_slot(aggregate_and_proof.aggregate.data.slot);
 
-        let domain = fork_info.compute_domain(&DomainType::AggregateAndProof, epoch)?;
+        let domain = self.domain(fork_info, &DomainType::AggregateAndProof, epoch)?;
+
+        let max_validators_per_committee = self.spec.max_validators_per_committee();
+        compiled_limit(
+            "MAX_VALIDATORS_PER_COMMITTEE",
+            max_validators_per_committee,
+            &[MAX_VALIDATORS_PER_COMMITTEE],
+        )?;
+        InternalAggregateAndProof::<MAX_VALIDATORS_PER_COMMITTEE>::new(
+            aggregate_and_proof,
+            max_validators_per_committee,
+        )?
+        .compute_signing_root(&domain)
+    }
+
+    /// Signing root of an Electra aggregate (EIP-7549), whose `aggregation_bits` carry the
+    /// attesters of every committee of the slot.
+    pub fn signing_root_for_sign_aggregate_and_proof_electra(
+        &self,
+        aggregate_and_proof: &AggregateAndProofElectra,
+        fork_info: &ForkInfo,
+    ) -> Result<Hash256> {
+        let epoch = self
+            .spec
+            .compute_epoch_at_slot(aggregate_and_proof.aggregate.data.slot);
+
+        let domain = self.domain(fork_info, &DomainType::AggregateAndProof, epoch)?;
+
+        let max_committees_per_slot = self.spec.max_committees_per_slot();
+        let max_aggregation_bits =
+            self.spec.max_validators_per_committee() * max_committees_per_slot;
+        let limits = (
+            compiled_limit(
+                "MAX_VALIDATORS_PER_COMMITTEE * MAX_COMMITTEES_PER_SLOT",
+                max_aggregation_bits,
+                &[
+                    AGGREGATION_BITS_ELECTRA_MINIMAL,
+                    AGGREGATION_BITS_ELECTRA_MAINNET,
+                ],
+            )?,
+            compiled_limit(
+                "MAX_COMMITTEES_PER_SLOT",
+                max_committees_per_slot,
+                &[
+                    MAX_COMMITTEES_PER_SLOT_MINIMAL,
+                    MAX_COMMITTEES_PER_SLOT_MAINNET,
+                ],
+            )?,
+        );
+        let aggregate_and_proof_root = match limits {
+            (AGGREGATION_BITS_ELECTRA_MAINNET, MAX_COMMITTEES_PER_SLOT_MAINNET) => {
+                electra_aggregate_and_proof_root::<
+                    AGGREGATION_BITS_ELECTRA_MAINNET,
+                    MAX_COMMITTEES_PER_SLOT_MAINNET,
+                >
+            }
+            (AGGREGATION_BITS_ELECTRA_MAINNET, MAX_COMMITTEES_PER_SLOT_MINIMAL) => {
+                electra_aggregate_and_proof_root::<
+                    AGGREGATION_BITS_ELECTRA_MAINNET,
+                    MAX_COMMITTEES_PER_SLOT_MINIMAL,
+                >
+            }
+            (AGGREGATION_BITS_ELECTRA_MINIMAL, MAX_COMMITTEES_PER_SLOT_MAINNET) => {
+                electra_aggregate_and_proof_root::<
+                    AGGREGATION_BITS_ELECTRA_MINIMAL,
+                    MAX_COMMITTEES_PER_SLOT_MAINNET,
+                >
+            }
+            _ => {
+                electra_aggregate_and_proof_root::<
+                    AGGREGATION_BITS_ELECTRA_MINIMAL,
+                    MAX_COMMITTEES_PER_SLOT_MINIMAL,
+                >
+            }
+        };
+        aggregate_and_proof_root(
+            aggregate_and_proof,
+            max_aggregation_bits,
+            max_committees_per_slot,
+            &domain,
+        )
+    }
 
-        InternalAggregateAndProof::try_from(aggregate_and_proof)?.compute_signing_root(&domain)
+    /// Signing root of the aggregate of an `AGGREGATE_AND_PROOF_V2` request, hashed as the
+    /// aggregate of its `version` fork.
+    pub fn signing_root_for_sign_versioned_aggregate_and_proof(
+        &self,
+        aggregate_and_proof: &VersionedAggregateAndProof,
+        fork_info: &ForkInfo,
+    ) -> Result<Hash256> {
+        match aggregate_and_proof {
+            VersionedAggregateAndProof::Phase0(aggregate_and_proof)
+            | VersionedAggregateAndProof::Altair(aggregate_and_proof)
+            | VersionedAggregateAndProof::Bellatrix(aggregate_and_proof)
+            | VersionedAggregateAndProof::Capella(aggregate_and_proof)
+            | VersionedAggregateAndProof::Deneb(aggregate_and_proof) => {
+                self.signing_root_for_sign_aggregate_and_proof(aggregate_and_proof, fork_info)
+            }
+            VersionedAggregateAndProof::Electra(aggregate_and_proof) => self
+                .signing_root_for_sign_aggregate_and_proof_electra(aggregate_and_proof, fork_info),
+        }
     }
 
     pub fn signing_root_for_deposit(&self, deposit_message: &DepositMessage) -> Result<Hash256> {
@@ -91,6 +405,17 @@ impl<'a> SigningRootUtil<'a> {
         InternalDepositMessage::try_from(deposit_message)?.compute_signing_root(&domain)
     }
 
+    /// `deposit_message_root` of a deposit_data.json entry, the hash tree root of the message.
+    pub fn deposit_message_root(&self, deposit_message: &DepositMessage) -> Result<Hash256> {
+        hash_tree_root::<_, InternalDepositMessage>(deposit_message)
+    }
+
+    /// Hash tree root of the signed deposit, the `deposit_data_root` argument of the deposit
+    /// contract.
+    pub fn deposit_data_root(&self, deposit_data: &DepositData) -> Result<Hash256> {
+        hash_tree_root::<_, InternalDepositData>(deposit_data)
+    }
+
     pub fn signing_root_for_validator_registration(
         &self,
         validator_registration: &ValidatorRegistration,
@@ -101,13 +426,68 @@ impl<'a> SigningRootUtil<'a> {
             .compute_signing_root(&domain)
     }
 
+    /// Signing root of a Capella withdrawal credentials change. The domain is always computed with
+    /// the genesis fork version, only the `genesis_validators_root` of `fork_info` is used.
+    pub fn signing_root_for_bls_to_execution_change(
+        &self,
+        bls_to_execution_change: &BLSToExecutionChange,
+        fork_info: &ForkInfo,
+    ) -> Result<Hash256> {
+        if self.validate_fork_info {
+            self.verify_fork_info(fork_info, fork_info.fork.epoch)?;
+        }
+        let domain = compute_domain(
+            &DomainType::DomainBlsToExecutionChange,
+            self.spec.genesis_fork_version(),
+            &fork_info.genesis_validators_root,
+        )?;
+        InternalBLSToExecutionChange::try_from(bls_to_execution_change)?
+            .compute_signing_root(&domain)
+    }
+
+    /// Signing root of a Deneb blob sidecar, sized by the `FIELD_ELEMENTS_PER_BLOB` of the spec.
+    pub fn signing_root_for_blob_sidecar(
+        &self,
+        blob_sidecar: &BlobSidecar,
+        fork_info: &ForkInfo,
+    ) -> Result<Hash256> {
+        let epoch = self.spec.compute_epoch_at_slot(blob_sidecar.slot);
+        let domain = self.domain(fork_info, &DomainType::DomainBlobSidecar, epoch)?;
+
+        match self.spec.field_elements_per_blob() {
+            FIELD_ELEMENTS_PER_BLOB_MAINNET => InternalBlobSidecar::<
+                { FIELD_ELEMENTS_PER_BLOB_MAINNET * BYTES_PER_FIELD_ELEMENT },
+            >::try_from(blob_sidecar)?
+            .compute_signing_root(&domain),
+            FIELD_ELEMENTS_PER_BLOB_MINIMAL => InternalBlobSidecar::<
+                { FIELD_ELEMENTS_PER_BLOB_MINIMAL * BYTES_PER_FIELD_ELEMENT },
+            >::try_from(blob_sidecar)?
+            .compute_signing_root(&domain),
+            field_elements_per_blob => anyhow::bail!(
+                "Unsupported FIELD_ELEMENTS_PER_BLOB {}",
+                field_elements_per_blob
+            ),
+        }
+    }
+
+    pub fn signing_root_for_blinded_blob_sidecar(
+        &self,
+        blinded_blob_sidecar: &BlindedBlobSidecar,
+        fork_info: &ForkInfo,
+    ) -> Result<Hash256> {
+        let epoch = self.spec.compute_epoch_at_slot(blinded_blob_sidecar.slot);
+        let domain = self.domain(fork_info, &DomainType::DomainBlobSidecar, epoch)?;
+
+        InternalBlindedBlobSidecar::try_from(blinded_blob_sidecar)?.compute_signing_root(&domain)
+    }
+
     pub fn signing_root_for_sync_committee_message(
         &self,
         sync_committee_message: &SyncCommitteeMessage,
         fork_info: &ForkInfo,
     ) -> Result<Hash256> {
         let epoch = self.spec.compute_epoch_at_slot(sync_committee_message.slot);
-        let domain = fork_info.compute_domain(&DomainType::SyncCommittee, epoch)?;
+        let domain = self.domain(fork_info, &DomainType::SyncCommittee, epoch)?;
 
         sync_committee_message.compute_signing_root(&domain)
     }
@@ -121,7 +501,7 @@ impl<'a> SigningRootUtil<'a> {
             .spec
             .compute_epoch_at_slot(sync_aggregator_selection_data.slot);
 
-        let domain = fork_info.compute_domain(&DomainType::SyncCommitteeSelectionProof, epoch)?;
+        let domain = self.domain(fork_info, &DomainType::SyncCommitteeSelectionProof, epoch)?;
 
         InternalSyncAggregatorSelectionData::try_from(sync_aggregator_selection_data)?
             .compute_signing_root(&domain)
@@ -136,18 +516,228 @@ impl<'a> SigningRootUtil<'a> {
             .spec
             .compute_epoch_at_slot(contribution_and_proof.contribution.slot);
 
-        let domain = fork_info.compute_domain(&DomainType::ContributionAndProof, epoch)?;
+        let domain = self.domain(fork_info, &DomainType::ContributionAndProof, epoch)?;
 
-        if self.spec.is_minimal_preset() {
-            InternalContributionAndProof::<SYNC_COMMITTEE_CONT_SIZE_MIMIMAL>::try_from(
+        let size = self.spec.sync_committee_contribution_size();
+        match compiled_limit(
+            "SYNC_COMMITTEE_SIZE / SYNC_COMMITTEE_SUBNET_COUNT",
+            size,
+            &[
+                SYNC_COMMITTEE_CONT_SIZE_MIMIMAL,
+                SYNC_COMMITTEE_CONT_SIZE_MAINNET,
+            ],
+        )? {
+            SYNC_COMMITTEE_CONT_SIZE_MIMIMAL => InternalContributionAndProof::<
+                SYNC_COMMITTEE_CONT_SIZE_MIMIMAL,
+            >::new(contribution_and_proof, size)?
+            .compute_signing_root(&domain),
+            _ => InternalContributionAndProof::<SYNC_COMMITTEE_CONT_SIZE_MAINNET>::new(
                 contribution_and_proof,
+                size,
             )?
-            .compute_signing_root(&domain)
-        } else {
-            InternalContributionAndProof::<SYNC_COMMITTEE_CONT_SIZE_MAINNET>::try_from(
+            .compute_signing_root(&domain),
+        }
+    }
+
+    /// Compute the signing root of the payload of a signing request.
+    pub fn signing_root(&self, signing_request: &SigningRequest) -> Result<Hash256> {
+        match signing_request {
+            SigningRequest::BlockV2 {
+                fork_info,
+                beacon_block,
+                ..
+            } => self.signing_root_for_sign_block_header(
+                &self.block_request_header(beacon_block)?,
+                fork_info,
+            ),
+            SigningRequest::Attestation {
+                fork_info,
+                attestation,
+                ..
+            } => self.signing_root_for_sign_attestation_data(attestation, fork_info),
+            SigningRequest::AggregationSlot {
+                fork_info,
+                aggregation_slot,
+                ..
+            } => self.signing_root_for_sign_aggegation_slot(aggregation_slot, fork_info),
+            SigningRequest::AggregateAndProof {
+                fork_info,
+                aggregate_and_proof,
+                ..
+            } => self.signing_root_for_sign_aggregate_and_proof(aggregate_and_proof, fork_info),
+            SigningRequest::AggregateAndProofV2 {
+                fork_info,
+                aggregate_and_proof,
+                ..
+            } => self.signing_root_for_sign_versioned_aggregate_and_proof(
+                aggregate_and_proof,
+                fork_info,
+            ),
+            SigningRequest::RandaoReveal {
+                fork_info,
+                randao_reveal,
+                ..
+            } => self.signing_root_for_randao_reveal(randao_reveal, fork_info),
+            SigningRequest::VoluntaryExit {
+                fork_info,
+                voluntary_exit,
+                ..
+            } => self.signing_root_for_voluntary_exit(voluntary_exit, fork_info),
+            SigningRequest::Deposit { deposit, .. } => self.signing_root_for_deposit(deposit),
+            SigningRequest::ValidatorRegistration {
+                validator_registration,
+                ..
+            } => self.signing_root_for_validator_registration(validator_registration),
+            SigningRequest::SyncCommitteeMessage {
+                fork_info,
+                sync_committee_message,
+                ..
+            } => self.signing_root_for_sync_committee_message(sync_committee_message, fork_info),
+            SigningRequest::SyncCommitteeSelectionProof {
+                fork_info,
+                sync_aggregator_selection_data,
+                ..
+            } => self.signing_root_for_sync_aggregator_selection_data(
+                sync_aggregator_selection_data,
+                fork_info,
+            ),
+            SigningRequest::SyncCommitteeContributionAndProof {
+                fork_info,
                 contribution_and_proof,
-            )?
-            .compute_signing_root(&domain)
+                ..
+            } => self.signing_root_for_sync_committee_contribution_and_proof(
+                contribution_and_proof,
+                fork_info,
+            ),
+            SigningRequest::BlsToExecutionChange {
+                fork_info,
+                bls_to_execution_change,
+                ..
+            } => self.signing_root_for_bls_to_execution_change(bls_to_execution_change, fork_info),
+            SigningRequest::BlobSidecar {
+                fork_info,
+                blob_sidecar,
+                ..
+            } => self.signing_root_for_blob_sidecar(blob_sidecar, fork_info),
+            SigningRequest::BlindedBlobSidecar {
+                fork_info,
+                blinded_blob_sidecar,
+                ..
+            } => self.signing_root_for_blinded_blob_sidecar(blinded_blob_sidecar, fork_info),
+        }
+    }
+
+    /// Compute the signing root of a signing request, verifying it against the `signingRoot`
+    /// supplied by the client.
+    pub fn verified_signing_root(&self, signing_request: &SigningRequest) -> Result<Hash256> {
+        let computed = self.signing_root(signing_request)?;
+        match signing_request.signing_root() {
+            Some(provided) if *provided != computed => Err(SigningRootError::SigningRootMismatch {
+                artifact_type: signing_request.artifact_type(),
+                provided: *provided,
+                computed,
+            }
+            .into()),
+            _ => Ok(computed),
+        }
+    }
+}
+
+/// The limit of `compiled` used for the SSZ bitlist or bitvector limit of the spec. Limits of the
+/// same merkle depth hash alike, so the smallest compiled one at least as large is used and the
+/// actual limit is checked at runtime.
+fn compiled_limit(name: &str, limit: usize, compiled: &[usize]) -> Result<usize> {
+    let depth = |limit: usize| ((limit + 255) / 256).next_power_of_two();
+    compiled
+        .iter()
+        .copied()
+        .filter(|compiled| limit > 0 && *compiled >= limit && depth(*compiled) == depth(limit))
+        .min()
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Unsupported {} {}, it must hash like one of {:?}",
+                name,
+                limit,
+                compiled
+            )
+        })
+}
+
+fn electra_aggregate_and_proof_root<
+    const MAX_AGGREGATION_BITS: usize,
+    const MAX_COMMITTEES_PER_SLOT: usize,
+>(
+    aggregate_and_proof: &AggregateAndProofElectra,
+    max_aggregation_bits: usize,
+    max_committees_per_slot: usize,
+    domain: &Hash256,
+) -> Result<Hash256> {
+    InternalAggregateAndProofElectra::<MAX_AGGREGATION_BITS, MAX_COMMITTEES_PER_SLOT>::new(
+        aggregate_and_proof,
+        max_aggregation_bits,
+        max_committees_per_slot,
+    )?
+    .compute_signing_root(domain)
+}
+
+/// Refuse bodies missing fields of `version` or carrying fields of later forks, which would
+/// otherwise not be part of the signed root.
+fn check_body_fields(version: ForkName, body: &BeaconBlockBody) -> Result<()> {
+    // presence of withdrawals, blob_gas_used and excess_blob_gas in the payload or its header
+    let execution_payload = match (&body.execution_payload, &body.execution_payload_header) {
+        (Some(payload), None) => Some((
+            payload.withdrawals.is_some(),
+            payload.blob_gas_used.is_some(),
+            payload.excess_blob_gas.is_some(),
+        )),
+        (None, Some(header)) => Some((
+            header.withdrawals_root.is_some(),
+            header.blob_gas_used.is_some(),
+            header.excess_blob_gas.is_some(),
+        )),
+        (None, None) => None,
+        (Some(_), Some(_)) => anyhow::bail!(
+            "Block body can't carry both execution_payload and execution_payload_header"
+        ),
+    };
+    // payload fields are only checked when there is a payload, a missing one is reported as such
+    let (withdrawals, blob_gas_used, excess_blob_gas) = execution_payload.unwrap_or((
+        version >= ForkName::Capella,
+        version >= ForkName::Deneb,
+        version >= ForkName::Deneb,
+    ));
+    let fields = [
+        (
+            "sync_aggregate",
+            ForkName::Altair,
+            body.sync_aggregate.is_some(),
+        ),
+        (
+            "execution_payload",
+            ForkName::Bellatrix,
+            execution_payload.is_some(),
+        ),
+        (
+            "bls_to_execution_changes",
+            ForkName::Capella,
+            body.bls_to_execution_changes.is_some(),
+        ),
+        ("withdrawals", ForkName::Capella, withdrawals),
+        ("blob_gas_used", ForkName::Deneb, blob_gas_used),
+        ("excess_blob_gas", ForkName::Deneb, excess_blob_gas),
+        (
+            "blob_kzg_commitments",
+            ForkName::Deneb,
+            body.blob_kzg_commitments.is_some(),
+        ),
+    ];
+
+    for (name, fork, present) in fields {
+        match (version >= fork, present) {
+            (true, false) => anyhow::bail!("{:?} block body is missing {}", version, name),
+            (false, true) => anyhow::bail!("{} is not part of a {:?} block body", name, version),
+            _ => {}
         }
     }
+    Ok(())
 }
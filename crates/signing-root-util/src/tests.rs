@@ -310,6 +310,49 @@ fn signing_root_for_deposit_is_calculated() {
     assert_eq!(computed_signing_root, expected_signing_root);
 }
 
+#[test]
+fn deposit_message_and_data_roots_are_calculated() {
+    let deposit_message = DepositMessage {
+        pubkey: hex!("b3e445d43871965d890a398f719348a1405ac72e35b92727cc570026f54471af7ea7b2040622a8fd0b5bfb2a209b5911").to_vec(),
+        withdrawal_credentials: Hash256::from(hex!(
+            "00eca1f12f398e3ceef109f5f76d8e99f9105e800a90390f1a18895919fd4b3b"
+        )),
+        amount: 32000000000,
+        genesis_fork_version: [0, 0, 0, 0],
+    };
+    let deposit_data = DepositData {
+        pubkey: deposit_message.pubkey.clone(),
+        withdrawal_credentials: deposit_message.withdrawal_credentials,
+        amount: deposit_message.amount,
+        signature: hex!("91a123edabc90547f7ac0320a4ea2967940f3b1a5bef396d2c36ff2a4cdbf5c117b257983d1044b9e954303b29ce6962006c6a4a5fb68cf97adcc77c7df47cb83ce8a910f921b94e36c94e2ca13054b4d1684562f43075373fe3045ee9b0b364").to_vec(),
+    };
+
+    let spec = Spec::new("mainnet").unwrap();
+    let signing_root_util = SigningRootUtil::new(&spec);
+
+    assert_eq!(
+        signing_root_util
+            .deposit_message_root(&deposit_message)
+            .unwrap()
+            .as_fixed_bytes(),
+        &hex!("e5f649f0154082253653461a36815b23c934a01d894fdc1c6dd91785aeac1d24")
+    );
+    assert_eq!(
+        signing_root_util
+            .deposit_data_root(&deposit_data)
+            .unwrap()
+            .as_fixed_bytes(),
+        &hex!("54d660cc52c015c9ceb1816c877c176b5e893a50f50ff10acfd91759448b3516")
+    );
+    assert_eq!(
+        signing_root_util
+            .signing_root_for_deposit(&deposit_message)
+            .unwrap()
+            .as_fixed_bytes(),
+        &hex!("1b552a79c495c8da2b0c2036e5f082f4856ed1896bf19457e60a5753811fab71")
+    );
+}
+
 #[test]
 fn signing_root_for_validator_registration_is_calculated() {
     let json_str = r#"{
@@ -438,3 +481,642 @@ fn signing_root_for_sync_committee_contribution_and_proof_is_calculated() {
 
     assert_eq!(computed_signing_root, expected_signing_root);
 }
+
+/// Signing request bodies as sent by Teku to Web3Signer, with the `signingRoot` the client computed.
+const SIGNING_REQUEST_FIXTURES: [&str; 15] = [
+    include_str!("../tests/fixtures/block_v2.json"),
+    include_str!("../tests/fixtures/attestation.json"),
+    include_str!("../tests/fixtures/aggregation_slot.json"),
+    include_str!("../tests/fixtures/aggregate_and_proof.json"),
+    include_str!("../tests/fixtures/randao_reveal.json"),
+    include_str!("../tests/fixtures/voluntary_exit.json"),
+    include_str!("../tests/fixtures/deposit.json"),
+    include_str!("../tests/fixtures/validator_registration.json"),
+    include_str!("../tests/fixtures/sync_committee_message.json"),
+    include_str!("../tests/fixtures/sync_committee_selection_proof.json"),
+    include_str!("../tests/fixtures/sync_committee_contribution_and_proof.json"),
+    include_str!("../tests/fixtures/bls_to_execution_change.json"),
+    include_str!("../tests/fixtures/blob_sidecar.json"),
+    include_str!("../tests/fixtures/blinded_blob_sidecar.json"),
+    include_str!("../tests/fixtures/aggregate_and_proof_electra.json"),
+];
+
+#[test]
+fn signing_root_is_dispatched_by_request_type() {
+    let spec = Spec::new("minimal").unwrap();
+    let signing_root_util = SigningRootUtil::new(&spec);
+
+    for fixture in SIGNING_REQUEST_FIXTURES {
+        let signing_request: SigningRequest = serde_json::from_str(fixture).unwrap();
+        let expected_signing_root: serde_json::Value = serde_json::from_str(fixture).unwrap();
+        let expected_signing_root: Hash256 =
+            serde_json::from_value(expected_signing_root["signingRoot"].clone()).unwrap();
+
+        assert_eq!(
+            signing_root_util.signing_root(&signing_request).unwrap(),
+            expected_signing_root,
+            "{}",
+            fixture
+        );
+    }
+}
+
+#[test]
+fn bls_to_execution_change_domain_uses_genesis_fork_version() {
+    let spec = Spec::new("minimal").unwrap();
+    let signing_root_util = SigningRootUtil::new(&spec);
+    let (bls_to_execution_change, mut fork_info) = match serde_json::from_str(include_str!(
+        "../tests/fixtures/bls_to_execution_change.json"
+    ))
+    .unwrap()
+    {
+        SigningRequest::BlsToExecutionChange {
+            bls_to_execution_change,
+            fork_info,
+            ..
+        } => (bls_to_execution_change, fork_info),
+        other => panic!("unexpected signing request {:?}", other),
+    };
+    let expected = Hash256::from(hex!(
+        "a614b7092cbdaa6815a3417845e5465644852e88aafa989860c72ded3c1eb3be"
+    ));
+
+    // the fork of the request doesn't change the domain
+    fork_info.fork.current_version = [4, 0, 0, 1];
+    fork_info.fork.epoch = 100;
+    assert_eq!(
+        signing_root_util
+            .signing_root_for_bls_to_execution_change(&bls_to_execution_change, &fork_info)
+            .unwrap(),
+        expected
+    );
+
+    fork_info.genesis_validators_root = Hash256::zero();
+    assert_ne!(
+        signing_root_util
+            .signing_root_for_bls_to_execution_change(&bls_to_execution_change, &fork_info)
+            .unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn blob_sidecar_is_sized_by_field_elements_per_blob() {
+    let (blob_sidecar, fork_info) =
+        match serde_json::from_str(include_str!("../tests/fixtures/blob_sidecar.json")).unwrap() {
+            SigningRequest::BlobSidecar {
+                blob_sidecar,
+                fork_info,
+                ..
+            } => (blob_sidecar, fork_info),
+            other => panic!("unexpected signing request {:?}", other),
+        };
+
+    // the minimal blob is 4 field elements, mainnet expects 4096
+    let spec = Spec::new("mainnet").unwrap();
+    let error = SigningRootUtil::new(&spec)
+        .signing_root_for_blob_sidecar(&blob_sidecar, &fork_info)
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Error converting blob to ssz Vector of 131072"
+    );
+}
+
+#[test]
+fn aggregate_and_proof_v2_is_hashed_as_the_aggregate_of_its_version() {
+    let (aggregate_and_proof, fork_info) = match serde_json::from_str(include_str!(
+        "../tests/fixtures/aggregate_and_proof_electra.json"
+    ))
+    .unwrap()
+    {
+        SigningRequest::AggregateAndProofV2 {
+            aggregate_and_proof: VersionedAggregateAndProof::Electra(aggregate_and_proof),
+            fork_info,
+            ..
+        } => (aggregate_and_proof, fork_info),
+        other => panic!("unexpected signing request {:?}", other),
+    };
+
+    // the committee_bits of the minimal preset don't fit the 64 committees of mainnet
+    let spec = Spec::new("mainnet").unwrap();
+    assert!(SigningRootUtil::new(&spec)
+        .signing_root_for_sign_aggregate_and_proof_electra(&aggregate_and_proof, &fork_info)
+        .is_err());
+
+    // aggregates of earlier forks have the signing root of an AGGREGATE_AND_PROOF request
+    let fixture = include_str!("../tests/fixtures/aggregate_and_proof.json");
+    let mut request: serde_json::Value = serde_json::from_str(fixture).unwrap();
+    request["type"] = "AGGREGATE_AND_PROOF_V2".into();
+    request["aggregate_and_proof"] = serde_json::json!({
+        "version": "DENEB",
+        "data": request["aggregate_and_proof"],
+    });
+    let signing_request: SigningRequest = serde_json::from_value(request).unwrap();
+
+    let spec = Spec::new("minimal").unwrap();
+    assert_eq!(
+        SigningRootUtil::new(&spec)
+            .signing_root(&signing_request)
+            .unwrap(),
+        fixture_signing_root(fixture)
+    );
+}
+
+#[test]
+fn signing_request_round_trips_through_json() {
+    for fixture in SIGNING_REQUEST_FIXTURES {
+        let signing_request: SigningRequest = serde_json::from_str(fixture).unwrap();
+        let json = serde_json::to_value(&signing_request).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::from_str::<serde_json::Value>(fixture).unwrap()
+        );
+    }
+}
+
+#[test]
+fn signing_root_is_optional_in_signing_request() {
+    let json = r#"{
+        "type": "RANDAO_REVEAL",
+        "fork_info": {
+            "fork": {
+                "previous_version": "0x00000001",
+                "current_version": "0x00000001",
+                "epoch": "1"
+            },
+            "genesis_validators_root": "0x04700007fabc8282644aed6d1c7c9e21d38a03a0c4ba193f3afe428824b3a673"
+        },
+        "randao_reveal": { "epoch": "3" }
+    }"#;
+
+    let signing_request: SigningRequest = serde_json::from_str(json).unwrap();
+    match signing_request {
+        SigningRequest::RandaoReveal { signing_root, .. } => assert_eq!(signing_root, None),
+        other => panic!("unexpected signing request {:?}", other),
+    }
+}
+
+#[test]
+fn unknown_signing_request_type_is_rejected() {
+    let json = r#"{ "type": "UNKNOWN", "randao_reveal": { "epoch": "3" } }"#;
+
+    assert!(serde_json::from_str::<SigningRequest>(json).is_err());
+}
+
+#[test]
+fn supplied_signing_root_is_verified() {
+    let spec = Spec::new("minimal").unwrap();
+    let signing_root_util = SigningRootUtil::new(&spec);
+
+    for fixture in SIGNING_REQUEST_FIXTURES {
+        let signing_request: SigningRequest = serde_json::from_str(fixture).unwrap();
+        assert_eq!(
+            signing_root_util
+                .verified_signing_root(&signing_request)
+                .unwrap(),
+            *signing_request.signing_root().unwrap()
+        );
+    }
+}
+
+#[test]
+fn mismatched_signing_root_is_rejected() {
+    let spec = Spec::new("minimal").unwrap();
+    let signing_root_util = SigningRootUtil::new(&spec);
+    let mut signing_request: SigningRequest =
+        serde_json::from_str(include_str!("../tests/fixtures/attestation.json")).unwrap();
+    if let SigningRequest::Attestation { signing_root, .. } = &mut signing_request {
+        *signing_root = Some(Hash256::repeat_byte(1));
+    }
+
+    let error = signing_root_util
+        .verified_signing_root(&signing_request)
+        .unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<SigningRootError>(),
+        Some(SigningRootError::SigningRootMismatch {
+            artifact_type: "ATTESTATION",
+            ..
+        })
+    ));
+    assert!(error.to_string().contains("ATTESTATION"));
+}
+
+/// `BLOCK_V2` requests carrying the full block of every fork. They are not consensus-spec
+/// `ssz_static` vectors, their `signingRoot` is recomputed by `tests/fixtures/block_roots.py`.
+const BLOCK_FIXTURES: [&str; 5] = [
+    include_str!("../tests/fixtures/block_v2_phase0.json"),
+    include_str!("../tests/fixtures/block_v2_altair.json"),
+    include_str!("../tests/fixtures/block_v2_bellatrix.json"),
+    include_str!("../tests/fixtures/block_v2_capella.json"),
+    include_str!("../tests/fixtures/block_v2_deneb.json"),
+];
+
+#[test]
+fn signing_root_for_sign_block_is_calculated_for_every_fork() {
+    let spec = Spec::new("minimal").unwrap();
+    let signing_root_util = SigningRootUtil::new(&spec);
+
+    for fixture in BLOCK_FIXTURES {
+        let signing_request: SigningRequest = serde_json::from_str(fixture).unwrap();
+        assert_eq!(
+            signing_root_util
+                .verified_signing_root(&signing_request)
+                .unwrap(),
+            *signing_request.signing_root().unwrap(),
+            "{}",
+            fixture
+        );
+
+        let json = serde_json::to_value(&signing_request).unwrap();
+        assert_eq!(
+            json,
+            serde_json::from_str::<serde_json::Value>(fixture).unwrap()
+        );
+    }
+}
+
+fn block_request(fixture: &str) -> (BlockRequest, ForkInfo) {
+    match serde_json::from_str(fixture).unwrap() {
+        SigningRequest::BlockV2 {
+            beacon_block,
+            fork_info,
+            ..
+        } => (beacon_block, fork_info),
+        other => panic!("unexpected signing request {:?}", other),
+    }
+}
+
+#[test]
+fn signing_root_of_block_equals_signing_root_of_its_header() {
+    let spec = Spec::new("minimal").unwrap();
+    let signing_root_util = SigningRootUtil::new(&spec);
+    let (block_request, fork_info) = block_request(BLOCK_FIXTURES[4]);
+    let block = block_request.block.unwrap();
+
+    let block_header = signing_root_util
+        .block_header(block_request.version, &block)
+        .unwrap();
+    assert_eq!(
+        block_header.body_root,
+        Hash256::from(hex!(
+            "ed791c200442eb769a3b59e00ce92a51ae220ebac2681db6d30b2d8a3d0ac07a"
+        ))
+    );
+    assert_eq!(
+        signing_root_util
+            .signing_root_for_sign_block(block_request.version, &block, &fork_info)
+            .unwrap(),
+        signing_root_util
+            .signing_root_for_sign_block_header(&block_header, &fork_info)
+            .unwrap()
+    );
+}
+
+#[test]
+fn block_body_fields_must_match_fork() {
+    let spec = Spec::new("minimal").unwrap();
+    let signing_root_util = SigningRootUtil::new(&spec);
+
+    let (block_request, _) = block_request(BLOCK_FIXTURES[3]);
+    let mut block = block_request.block.unwrap();
+    let error = signing_root_util
+        .block_header(ForkName::Deneb, &block)
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Deneb block body is missing blob_gas_used"
+    );
+
+    let error = signing_root_util
+        .block_header(ForkName::Bellatrix, &block)
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "bls_to_execution_changes is not part of a Bellatrix block body"
+    );
+
+    block.body.execution_payload = None;
+    let error = signing_root_util
+        .block_header(ForkName::Capella, &block)
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Capella block body is missing execution_payload"
+    );
+}
+
+const BLINDED_BLOCK_FIXTURES: [(&str, &str); 3] = [
+    (
+        include_str!("../tests/fixtures/block_v2_bellatrix_blinded.json"),
+        BLOCK_FIXTURES[2],
+    ),
+    (
+        include_str!("../tests/fixtures/block_v2_capella_blinded.json"),
+        BLOCK_FIXTURES[3],
+    ),
+    (
+        include_str!("../tests/fixtures/block_v2_deneb_blinded.json"),
+        BLOCK_FIXTURES[4],
+    ),
+];
+
+#[test]
+fn signing_root_of_blinded_block_equals_signing_root_of_full_block() {
+    let spec = Spec::new("minimal").unwrap();
+    let signing_root_util = SigningRootUtil::new(&spec);
+
+    for (blinded_fixture, full_fixture) in BLINDED_BLOCK_FIXTURES {
+        let blinded: SigningRequest = serde_json::from_str(blinded_fixture).unwrap();
+        let full: SigningRequest = serde_json::from_str(full_fixture).unwrap();
+        let signing_root = signing_root_util.verified_signing_root(&blinded).unwrap();
+        assert_eq!(signing_root, *blinded.signing_root().unwrap());
+        assert_eq!(signing_root, signing_root_util.signing_root(&full).unwrap());
+
+        let json = serde_json::to_value(&blinded).unwrap();
+        assert_eq!(
+            json,
+            serde_json::from_str::<serde_json::Value>(blinded_fixture).unwrap()
+        );
+    }
+}
+
+#[test]
+fn block_body_can_not_carry_both_execution_payload_and_header() {
+    let spec = Spec::new("minimal").unwrap();
+    let signing_root_util = SigningRootUtil::new(&spec);
+
+    let (full_request, _) = block_request(BLOCK_FIXTURES[4]);
+    let (blinded_request, _) = block_request(BLINDED_BLOCK_FIXTURES[2].0);
+    let mut block = full_request.block.unwrap();
+    block.body.execution_payload_header =
+        blinded_request.block.unwrap().body.execution_payload_header;
+
+    let error = signing_root_util
+        .block_header(ForkName::Deneb, &block)
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Block body can't carry both execution_payload and execution_payload_header"
+    );
+}
+
+#[test]
+fn electra_blocks_are_signed_by_their_header() {
+    let spec = Spec::new("minimal").unwrap();
+    let signing_root_util = SigningRootUtil::new(&spec);
+    let (full_request, fork_info) = block_request(BLOCK_FIXTURES[4]);
+    let block = full_request.block.unwrap();
+    let block_header = signing_root_util
+        .block_header(ForkName::Deneb, &block)
+        .unwrap();
+
+    let signing_request: SigningRequest = serde_json::from_value(serde_json::json!({
+        "type": "BLOCK_V2",
+        "fork_info": fork_info,
+        "beacon_block": { "version": "ELECTRA", "block_header": block_header },
+    }))
+    .unwrap();
+    assert_eq!(
+        signing_root_util.signing_root(&signing_request).unwrap(),
+        signing_root_util
+            .signing_root_for_sign_block_header(&block_header, &fork_info)
+            .unwrap()
+    );
+
+    // full and blinded bodies are checked against the fork before being refused
+    let (blinded_request, _) = block_request(BLINDED_BLOCK_FIXTURES[2].0);
+    for block in [&block, &blinded_request.block.unwrap()] {
+        let error = signing_root_util
+            .block_header(ForkName::Electra, block)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Electra block bodies are not supported, send the block_header instead"
+        );
+    }
+    let (capella_request, _) = block_request(BLOCK_FIXTURES[3]);
+    let error = signing_root_util
+        .block_header(ForkName::Electra, &capella_request.block.unwrap())
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Electra block body is missing blob_gas_used"
+    );
+}
+
+#[test]
+fn block_request_requires_block_or_block_header() {
+    let spec = Spec::new("minimal").unwrap();
+    let signing_root_util = SigningRootUtil::new(&spec);
+    let block_request = BlockRequest {
+        version: ForkName::Phase0,
+        block: None,
+        block_header: None,
+    };
+
+    assert!(signing_root_util
+        .block_request_header(&block_request)
+        .is_err());
+}
+
+/// Minimal spec with its preset values replaced by `preset_overrides`.
+fn custom_spec(preset_overrides: &str) -> Spec {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("config.yaml");
+    std::fs::write(
+        &config_path,
+        [
+            include_str!("../../specs/configs/minimal/config.yaml"),
+            preset_overrides,
+        ]
+        .join("\n"),
+    )
+    .unwrap();
+    Spec::new(config_path.to_str().unwrap()).unwrap()
+}
+
+fn fixture_signing_root(fixture: &str) -> Hash256 {
+    *serde_json::from_str::<SigningRequest>(fixture)
+        .unwrap()
+        .signing_root()
+        .unwrap()
+}
+
+#[test]
+fn bit_sizes_of_custom_spec_hashing_like_a_preset_are_supported() {
+    let spec = custom_spec("MAX_VALIDATORS_PER_COMMITTEE: 1500\nSYNC_COMMITTEE_SIZE: 48");
+    let signing_root_util = SigningRootUtil::new(&spec);
+
+    for fixture in [
+        include_str!("../tests/fixtures/aggregate_and_proof.json"),
+        include_str!("../tests/fixtures/aggregate_and_proof_electra.json"),
+    ] {
+        let signing_request: SigningRequest = serde_json::from_str(fixture).unwrap();
+        assert_eq!(
+            signing_root_util.signing_root(&signing_request).unwrap(),
+            fixture_signing_root(fixture)
+        );
+    }
+
+    // 12 contribution bits take 2 bytes and hash like the 8 bits of the minimal preset
+    let fixture = include_str!("../tests/fixtures/sync_committee_contribution_and_proof.json");
+    let mut signing_request: SigningRequest = serde_json::from_str(fixture).unwrap();
+    assert!(signing_root_util.signing_root(&signing_request).is_err());
+    if let SigningRequest::SyncCommitteeContributionAndProof {
+        contribution_and_proof,
+        ..
+    } = &mut signing_request
+    {
+        contribution_and_proof.contribution.aggregation_bits = vec![0x24, 0x00];
+    }
+    assert_eq!(
+        signing_root_util.signing_root(&signing_request).unwrap(),
+        fixture_signing_root(fixture)
+    );
+}
+
+#[test]
+fn bits_beyond_the_limit_of_custom_spec_are_rejected() {
+    let spec = custom_spec("MAX_VALIDATORS_PER_COMMITTEE: 1500");
+    let signing_root_util = SigningRootUtil::new(&spec);
+    let mut signing_request: SigningRequest =
+        serde_json::from_str(include_str!("../tests/fixtures/aggregate_and_proof.json")).unwrap();
+    if let SigningRequest::AggregateAndProof {
+        aggregate_and_proof,
+        ..
+    } = &mut signing_request
+    {
+        aggregate_and_proof.aggregate.aggregation_bits = [vec![0xff; 188], vec![0x01]].concat();
+    }
+
+    let error = signing_root_util
+        .signing_root(&signing_request)
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "aggregation_bits has 1504 bits, more than 1500"
+    );
+}
+
+#[test]
+fn unsupported_bit_sizes_of_custom_spec_are_rejected() {
+    let spec = custom_spec("MAX_VALIDATORS_PER_COMMITTEE: 100\nSYNC_COMMITTEE_SIZE: 1024");
+    let signing_root_util = SigningRootUtil::new(&spec);
+
+    let signing_request: SigningRequest =
+        serde_json::from_str(include_str!("../tests/fixtures/aggregate_and_proof.json")).unwrap();
+    let error = signing_root_util
+        .signing_root(&signing_request)
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Unsupported MAX_VALIDATORS_PER_COMMITTEE 100, it must hash like one of [2048]"
+    );
+
+    let signing_request: SigningRequest = serde_json::from_str(include_str!(
+        "../tests/fixtures/sync_committee_contribution_and_proof.json"
+    ))
+    .unwrap();
+    let error = signing_root_util
+        .signing_root(&signing_request)
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Unsupported SYNC_COMMITTEE_SIZE / SYNC_COMMITTEE_SUBNET_COUNT 256, it must hash like one of [8, 128]"
+    );
+}
+
+fn mainnet_fork_info(previous_version: [u8; 4], current_version: [u8; 4], epoch: u64) -> ForkInfo {
+    ForkInfo {
+        fork: Fork {
+            previous_version,
+            current_version,
+            epoch,
+        },
+        genesis_validators_root: Hash256::from(hex!(
+            "4b363db94e286120d76eb905340fdd4e54bfe9f06bf33ff6cf5ad27f511bfe95"
+        )),
+    }
+}
+
+#[test]
+fn domain_is_computed_from_fork_schedule() {
+    let spec = Spec::new("mainnet").unwrap();
+    let signing_root_util = SigningRootUtil::new(&spec);
+    let fork_info = mainnet_fork_info([2, 0, 0, 0], [3, 0, 0, 0], 194048);
+
+    for epoch in [194047, 194048, 200000] {
+        assert_eq!(
+            signing_root_util
+                .compute_domain(
+                    &DomainType::Randao,
+                    epoch,
+                    &fork_info.genesis_validators_root
+                )
+                .unwrap(),
+            fork_info
+                .compute_domain(&DomainType::Randao, epoch)
+                .unwrap()
+        );
+    }
+}
+
+#[test]
+fn fork_info_is_verified_against_fork_schedule() {
+    let spec = Spec::new("mainnet").unwrap();
+    let genesis_validators_root = spec.genesis_validators_root().map(Hash256::from);
+    let signing_root_util =
+        SigningRootUtil::new(&spec).with_fork_info_validation(genesis_validators_root);
+
+    let capella = mainnet_fork_info([2, 0, 0, 0], [3, 0, 0, 0], 194048);
+    signing_root_util
+        .verify_fork_info(&capella, 200000)
+        .unwrap();
+
+    let error = signing_root_util
+        .verify_fork_info(&capella, 269568)
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "fork_info does not match the fork schedule of the network: epoch 269568 is past the deneb fork at epoch 269568"
+    );
+
+    let other_network = mainnet_fork_info([2, 0, 0, 0x10], [3, 0, 0, 0x10], 194048);
+    let error = signing_root_util
+        .verify_fork_info(&other_network, 200000)
+        .unwrap_err();
+    assert!(error.to_string().contains("is not a fork of mainnet"));
+
+    let mut other_genesis = capella.clone();
+    other_genesis.genesis_validators_root = Hash256::zero();
+    assert!(matches!(
+        signing_root_util
+            .verify_fork_info(&other_genesis, 200000)
+            .unwrap_err()
+            .downcast::<SigningRootError>()
+            .unwrap(),
+        SigningRootError::ForkInfoMismatch(_)
+    ));
+}
+
+#[test]
+fn signing_request_fork_info_is_verified_when_enabled() {
+    let spec = Spec::new("minimal").unwrap();
+    let fixture = include_str!("../tests/fixtures/randao_reveal.json");
+    let mut signing_request: SigningRequest = serde_json::from_str(fixture).unwrap();
+
+    // the fixtures use a fork at epoch 1 which isn't part of the minimal fork schedule
+    SigningRootUtil::new(&spec)
+        .signing_root(&signing_request)
+        .unwrap();
+    let signing_root_util = SigningRootUtil::new(&spec).with_fork_info_validation(None);
+    assert!(signing_root_util.signing_root(&signing_request).is_err());
+
+    if let SigningRequest::RandaoReveal { fork_info, .. } = &mut signing_request {
+        fork_info.fork.epoch = 0;
+    }
+    signing_root_util.signing_root(&signing_request).unwrap();
+}
@@ -3,11 +3,20 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 pub type Hash256 = primitive_types::H256;
+pub type U256 = primitive_types::U256;
 
 #[derive(Error, Debug)]
 pub enum SigningRootError {
     #[error("Unexpected Error in converting vector to array")]
     VectorConversionError,
+    #[error("signingRoot {provided:?} of {artifact_type} does not match the computed signing root {computed:?}")]
+    SigningRootMismatch {
+        artifact_type: &'static str,
+        provided: Hash256,
+        computed: Hash256,
+    },
+    #[error("fork_info does not match the fork schedule of the network: {0}")]
+    ForkInfoMismatch(String),
 }
 
 #[derive(PartialEq, Eq, Debug, Default, Clone, Serialize, Deserialize)]
@@ -123,6 +132,40 @@ pub struct AggregateAndProof {
     pub selection_proof: Vec<u8>,
 }
 
+/// Electra attestation (EIP-7549), aggregating the committees of a slot flagged in
+/// `committee_bits`.
+#[derive(PartialEq, Eq, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct AttestationElectra {
+    #[serde(with = "serde_utils::hex_vec")]
+    pub aggregation_bits: Vec<u8>,
+    pub data: AttestationData,
+    #[serde(with = "serde_utils::hex_vec")]
+    pub signature: Vec<u8>,
+    #[serde(with = "serde_utils::hex_vec")]
+    pub committee_bits: Vec<u8>,
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct AggregateAndProofElectra {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub aggregator_index: u64,
+    pub aggregate: AttestationElectra,
+    #[serde(with = "serde_utils::hex_vec")]
+    pub selection_proof: Vec<u8>,
+}
+
+/// `aggregate_and_proof` of an `AGGREGATE_AND_PROOF_V2` request, the `data` of the `version` fork.
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "version", content = "data", rename_all = "UPPERCASE")]
+pub enum VersionedAggregateAndProof {
+    Phase0(AggregateAndProof),
+    Altair(AggregateAndProof),
+    Bellatrix(AggregateAndProof),
+    Capella(AggregateAndProof),
+    Deneb(AggregateAndProof),
+    Electra(AggregateAndProofElectra),
+}
+
 #[derive(PartialEq, Eq, Debug, Default, Clone, Serialize, Deserialize)]
 pub struct RandaoReveal {
     #[serde(with = "serde_utils::quoted_u64")]
@@ -196,3 +239,523 @@ pub struct ContributionAndProof {
     #[serde(with = "serde_utils::hex_vec")]
     pub selection_proof: Vec<u8>, //bls signature
 }
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SignedBeaconBlockHeader {
+    pub message: BeaconBlockHeader,
+    #[serde(with = "serde_utils::hex_vec")]
+    pub signature: Vec<u8>,
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ProposerSlashing {
+    pub signed_header_1: SignedBeaconBlockHeader,
+    pub signed_header_2: SignedBeaconBlockHeader,
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct IndexedAttestation {
+    #[serde(with = "serde_utils::quoted_u64_vec")]
+    pub attesting_indices: Vec<u64>,
+    pub data: AttestationData,
+    #[serde(with = "serde_utils::hex_vec")]
+    pub signature: Vec<u8>,
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct AttesterSlashing {
+    pub attestation_1: IndexedAttestation,
+    pub attestation_2: IndexedAttestation,
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Eth1Data {
+    pub deposit_root: Hash256,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub deposit_count: u64,
+    pub block_hash: Hash256,
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct DepositData {
+    #[serde(with = "serde_utils::hex_vec")]
+    pub pubkey: Vec<u8>,
+    pub withdrawal_credentials: Hash256,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub amount: u64,
+    #[serde(with = "serde_utils::hex_vec")]
+    pub signature: Vec<u8>,
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Deposit {
+    pub proof: Vec<Hash256>,
+    pub data: DepositData,
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SignedVoluntaryExit {
+    pub message: VoluntaryExit,
+    #[serde(with = "serde_utils::hex_vec")]
+    pub signature: Vec<u8>,
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SyncAggregate {
+    #[serde(with = "serde_utils::hex_vec")]
+    pub sync_committee_bits: Vec<u8>,
+    #[serde(with = "serde_utils::hex_vec")]
+    pub sync_committee_signature: Vec<u8>,
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Withdrawal {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub index: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub validator_index: u64,
+    #[serde(with = "serde_utils::hex_vec")]
+    pub address: Vec<u8>,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub amount: u64,
+}
+
+/// Execution payload of any fork from Bellatrix, fields added by later forks are optional.
+#[derive(PartialEq, Eq, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ExecutionPayload {
+    pub parent_hash: Hash256,
+    #[serde(with = "serde_utils::hex_vec")]
+    pub fee_recipient: Vec<u8>,
+    pub state_root: Hash256,
+    pub receipts_root: Hash256,
+    #[serde(with = "serde_utils::hex_vec")]
+    pub logs_bloom: Vec<u8>,
+    pub prev_randao: Hash256,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub block_number: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub gas_limit: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub gas_used: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub timestamp: u64,
+    #[serde(with = "serde_utils::hex_vec")]
+    pub extra_data: Vec<u8>,
+    #[serde(with = "serde_utils::quoted_u256")]
+    pub base_fee_per_gas: U256,
+    pub block_hash: Hash256,
+    #[serde(with = "serde_utils::list_of_bytes_lists")]
+    pub transactions: Vec<Vec<u8>>,
+    // Capella
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub withdrawals: Option<Vec<Withdrawal>>,
+    // Deneb
+    #[serde(
+        default,
+        with = "quoted_u64_opt",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub blob_gas_used: Option<u64>,
+    #[serde(
+        default,
+        with = "quoted_u64_opt",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub excess_blob_gas: Option<u64>,
+}
+
+/// Execution payload header of a blinded block of any fork from Bellatrix, fields added by later
+/// forks are optional.
+#[derive(PartialEq, Eq, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ExecutionPayloadHeader {
+    pub parent_hash: Hash256,
+    #[serde(with = "serde_utils::hex_vec")]
+    pub fee_recipient: Vec<u8>,
+    pub state_root: Hash256,
+    pub receipts_root: Hash256,
+    #[serde(with = "serde_utils::hex_vec")]
+    pub logs_bloom: Vec<u8>,
+    pub prev_randao: Hash256,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub block_number: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub gas_limit: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub gas_used: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub timestamp: u64,
+    #[serde(with = "serde_utils::hex_vec")]
+    pub extra_data: Vec<u8>,
+    #[serde(with = "serde_utils::quoted_u256")]
+    pub base_fee_per_gas: U256,
+    pub block_hash: Hash256,
+    pub transactions_root: Hash256,
+    // Capella
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub withdrawals_root: Option<Hash256>,
+    // Deneb
+    #[serde(
+        default,
+        with = "quoted_u64_opt",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub blob_gas_used: Option<u64>,
+    #[serde(
+        default,
+        with = "quoted_u64_opt",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub excess_blob_gas: Option<u64>,
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct BLSToExecutionChange {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub validator_index: u64,
+    #[serde(with = "serde_utils::hex_vec")]
+    pub from_bls_pubkey: Vec<u8>,
+    #[serde(with = "serde_utils::hex_vec")]
+    pub to_execution_address: Vec<u8>,
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SignedBLSToExecutionChange {
+    pub message: BLSToExecutionChange,
+    #[serde(with = "serde_utils::hex_vec")]
+    pub signature: Vec<u8>,
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct KzgCommitment(#[serde(with = "serde_utils::hex_vec")] pub Vec<u8>);
+
+/// Beacon block body of any fork, fields added after phase0 are optional. Blinded bodies carry
+/// the `execution_payload_header` instead of the `execution_payload`.
+#[derive(PartialEq, Eq, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct BeaconBlockBody {
+    #[serde(with = "serde_utils::hex_vec")]
+    pub randao_reveal: Vec<u8>,
+    pub eth1_data: Eth1Data,
+    pub graffiti: Hash256,
+    pub proposer_slashings: Vec<ProposerSlashing>,
+    pub attester_slashings: Vec<AttesterSlashing>,
+    pub attestations: Vec<Attestation>,
+    pub deposits: Vec<Deposit>,
+    pub voluntary_exits: Vec<SignedVoluntaryExit>,
+    // Altair
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_aggregate: Option<SyncAggregate>,
+    // Bellatrix
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_payload: Option<ExecutionPayload>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_payload_header: Option<ExecutionPayloadHeader>,
+    // Capella
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bls_to_execution_changes: Option<Vec<SignedBLSToExecutionChange>>,
+    // Deneb
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob_kzg_commitments: Option<Vec<KzgCommitment>>,
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct BeaconBlock {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub slot: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub proposer_index: u64,
+    pub parent_root: Hash256,
+    pub state_root: Hash256,
+    pub body: BeaconBlockBody,
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ForkName {
+    Phase0,
+    Altair,
+    Bellatrix,
+    Capella,
+    Deneb,
+    Electra,
+}
+
+/// `serde_utils::quoted_u64` of optional fields.
+mod quoted_u64_opt {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct QuotedU64(#[serde(with = "serde_utils::quoted_u64")] u64);
+
+    pub fn serialize<S: Serializer>(value: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error> {
+        value.map(QuotedU64).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<u64>, D::Error> {
+        Ok(Option::<QuotedU64>::deserialize(deserializer)?.map(|value| value.0))
+    }
+}
+
+/// `beacon_block` of a `BLOCK_V2` request, carrying either the full `block` or its
+/// `block_header`.
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub struct BlockRequest {
+    pub version: ForkName,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block: Option<Box<BeaconBlock>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_header: Option<BeaconBlockHeader>,
+}
+
+impl BlockRequest {
+    pub fn slot(&self) -> Option<u64> {
+        match (&self.block, &self.block_header) {
+            (_, Some(block_header)) => Some(block_header.slot),
+            (Some(block), None) => Some(block.slot),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Deneb blob sidecar of the devnets which sign each sidecar, the `blob` is
+/// `BYTES_PER_FIELD_ELEMENT * FIELD_ELEMENTS_PER_BLOB` bytes.
+#[derive(PartialEq, Eq, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct BlobSidecar {
+    pub block_root: Hash256,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub index: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub slot: u64,
+    pub block_parent_root: Hash256,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub proposer_index: u64,
+    #[serde(with = "serde_utils::hex_vec")]
+    pub blob: Vec<u8>,
+    #[serde(with = "serde_utils::hex_vec")]
+    pub kzg_commitment: Vec<u8>,
+    #[serde(with = "serde_utils::hex_vec")]
+    pub kzg_proof: Vec<u8>,
+}
+
+/// Blob sidecar carrying the hash tree root of the blob, it has the signing root of the full one.
+#[derive(PartialEq, Eq, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct BlindedBlobSidecar {
+    pub block_root: Hash256,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub index: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub slot: u64,
+    pub block_parent_root: Hash256,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub proposer_index: u64,
+    pub blob_root: Hash256,
+    #[serde(with = "serde_utils::hex_vec")]
+    pub kzg_commitment: Vec<u8>,
+    #[serde(with = "serde_utils::hex_vec")]
+    pub kzg_proof: Vec<u8>,
+}
+
+/// Web3Signer signing request body, tagged by `type`.
+///
+/// `signingRoot` is optional: when present it is the root the client expects to be signed.
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SigningRequest {
+    #[serde(rename = "BLOCK_V2")]
+    BlockV2 {
+        fork_info: ForkInfo,
+        beacon_block: BlockRequest,
+        #[serde(
+            rename = "signingRoot",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        signing_root: Option<Hash256>,
+    },
+    Attestation {
+        fork_info: ForkInfo,
+        attestation: AttestationData,
+        #[serde(
+            rename = "signingRoot",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        signing_root: Option<Hash256>,
+    },
+    AggregationSlot {
+        fork_info: ForkInfo,
+        aggregation_slot: AggregationSlot,
+        #[serde(
+            rename = "signingRoot",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        signing_root: Option<Hash256>,
+    },
+    AggregateAndProof {
+        fork_info: ForkInfo,
+        aggregate_and_proof: AggregateAndProof,
+        #[serde(
+            rename = "signingRoot",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        signing_root: Option<Hash256>,
+    },
+    #[serde(rename = "AGGREGATE_AND_PROOF_V2")]
+    AggregateAndProofV2 {
+        fork_info: ForkInfo,
+        aggregate_and_proof: VersionedAggregateAndProof,
+        #[serde(
+            rename = "signingRoot",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        signing_root: Option<Hash256>,
+    },
+    RandaoReveal {
+        fork_info: ForkInfo,
+        randao_reveal: RandaoReveal,
+        #[serde(
+            rename = "signingRoot",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        signing_root: Option<Hash256>,
+    },
+    VoluntaryExit {
+        fork_info: ForkInfo,
+        voluntary_exit: VoluntaryExit,
+        #[serde(
+            rename = "signingRoot",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        signing_root: Option<Hash256>,
+    },
+    Deposit {
+        deposit: DepositMessage,
+        #[serde(
+            rename = "signingRoot",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        signing_root: Option<Hash256>,
+    },
+    ValidatorRegistration {
+        validator_registration: ValidatorRegistration,
+        #[serde(
+            rename = "signingRoot",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        signing_root: Option<Hash256>,
+    },
+    SyncCommitteeMessage {
+        fork_info: ForkInfo,
+        sync_committee_message: SyncCommitteeMessage,
+        #[serde(
+            rename = "signingRoot",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        signing_root: Option<Hash256>,
+    },
+    SyncCommitteeSelectionProof {
+        fork_info: ForkInfo,
+        sync_aggregator_selection_data: SyncAggregatorSelectionData,
+        #[serde(
+            rename = "signingRoot",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        signing_root: Option<Hash256>,
+    },
+    SyncCommitteeContributionAndProof {
+        fork_info: ForkInfo,
+        contribution_and_proof: ContributionAndProof,
+        #[serde(
+            rename = "signingRoot",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        signing_root: Option<Hash256>,
+    },
+    BlsToExecutionChange {
+        fork_info: ForkInfo,
+        bls_to_execution_change: BLSToExecutionChange,
+        #[serde(
+            rename = "signingRoot",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        signing_root: Option<Hash256>,
+    },
+    BlobSidecar {
+        fork_info: ForkInfo,
+        blob_sidecar: BlobSidecar,
+        #[serde(
+            rename = "signingRoot",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        signing_root: Option<Hash256>,
+    },
+    BlindedBlobSidecar {
+        fork_info: ForkInfo,
+        blinded_blob_sidecar: BlindedBlobSidecar,
+        #[serde(
+            rename = "signingRoot",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        signing_root: Option<Hash256>,
+    },
+}
+
+impl SigningRequest {
+    /// The `type` of the request, naming the artifact to sign.
+    pub fn artifact_type(&self) -> &'static str {
+        match self {
+            SigningRequest::BlockV2 { .. } => "BLOCK_V2",
+            SigningRequest::Attestation { .. } => "ATTESTATION",
+            SigningRequest::AggregationSlot { .. } => "AGGREGATION_SLOT",
+            SigningRequest::AggregateAndProof { .. } => "AGGREGATE_AND_PROOF",
+            SigningRequest::AggregateAndProofV2 { .. } => "AGGREGATE_AND_PROOF_V2",
+            SigningRequest::RandaoReveal { .. } => "RANDAO_REVEAL",
+            SigningRequest::VoluntaryExit { .. } => "VOLUNTARY_EXIT",
+            SigningRequest::Deposit { .. } => "DEPOSIT",
+            SigningRequest::ValidatorRegistration { .. } => "VALIDATOR_REGISTRATION",
+            SigningRequest::SyncCommitteeMessage { .. } => "SYNC_COMMITTEE_MESSAGE",
+            SigningRequest::SyncCommitteeSelectionProof { .. } => "SYNC_COMMITTEE_SELECTION_PROOF",
+            SigningRequest::SyncCommitteeContributionAndProof { .. } => {
+                "SYNC_COMMITTEE_CONTRIBUTION_AND_PROOF"
+            }
+            SigningRequest::BlsToExecutionChange { .. } => "BLS_TO_EXECUTION_CHANGE",
+            SigningRequest::BlobSidecar { .. } => "BLOB_SIDECAR",
+            SigningRequest::BlindedBlobSidecar { .. } => "BLINDED_BLOB_SIDECAR",
+        }
+    }
+
+    /// The `signingRoot` supplied by the client, if any.
+    pub fn signing_root(&self) -> Option<&Hash256> {
+        match self {
+            SigningRequest::BlockV2 { signing_root, .. }
+            | SigningRequest::Attestation { signing_root, .. }
+            | SigningRequest::AggregationSlot { signing_root, .. }
+            | SigningRequest::AggregateAndProof { signing_root, .. }
+            | SigningRequest::AggregateAndProofV2 { signing_root, .. }
+            | SigningRequest::RandaoReveal { signing_root, .. }
+            | SigningRequest::VoluntaryExit { signing_root, .. }
+            | SigningRequest::Deposit { signing_root, .. }
+            | SigningRequest::ValidatorRegistration { signing_root, .. }
+            | SigningRequest::SyncCommitteeMessage { signing_root, .. }
+            | SigningRequest::SyncCommitteeSelectionProof { signing_root, .. }
+            | SigningRequest::SyncCommitteeContributionAndProof { signing_root, .. }
+            | SigningRequest::BlsToExecutionChange { signing_root, .. }
+            | SigningRequest::BlobSidecar { signing_root, .. }
+            | SigningRequest::BlindedBlobSidecar { signing_root, .. } => signing_root.as_ref(),
+        }
+    }
+}
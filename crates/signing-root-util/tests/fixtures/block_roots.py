@@ -0,0 +1,121 @@
+#!/usr/bin/env python3
+"""Recompute the signingRoot of the block_v2_*.json fixtures with a standalone SSZ
+hash_tree_root implementation of the minimal preset. They are not consensus-spec ssz_static
+test vectors, this script makes their expected roots reproducible.
+
+    python3 crates/signing-root-util/tests/fixtures/block_roots.py
+"""
+import glob
+import hashlib
+import json
+import os
+
+def h(a, b): return hashlib.sha256(a + b).digest()
+ZERO = [b"\0" * 32]
+for i in range(64): ZERO.append(h(ZERO[-1], ZERO[-1]))
+
+def next_pow2(n):
+    p = 1
+    while p < n: p *= 2
+    return p
+
+def merkleize(chunks, limit=None):
+    count = len(chunks)
+    if limit is None: limit = count
+    assert count <= limit
+    size = next_pow2(max(limit, 1))
+    depth = size.bit_length() - 1
+    layer = list(chunks)
+    for d in range(depth):
+        if len(layer) % 2 == 1: layer.append(ZERO[d])
+        layer = [h(layer[i], layer[i + 1]) for i in range(0, len(layer), 2)]
+    return layer[0] if layer else ZERO[depth]
+
+def mix(root, n): return h(root, n.to_bytes(32, "little"))
+def pack(b):
+    if len(b) % 32: b = b + b"\0" * (32 - len(b) % 32)
+    return [b[i:i+32] for i in range(0, len(b), 32)] or []
+
+def u64(v): return int(v).to_bytes(8, "little") + b"\0" * 24
+def u256(v): return int(v).to_bytes(32, "little")
+def hexb(s): return bytes.fromhex(s[2:])
+def bytes_vec(s):  # ByteVector[N]
+    b = hexb(s); return merkleize(pack(b), (len(b) + 31) // 32)
+def byte_list(s, limit): b = hexb(s); return mix(merkleize(pack(b), (limit + 31) // 32), len(b))
+def u64_list(vals, limit):
+    b = b"".join(int(v).to_bytes(8, "little") for v in vals)
+    return mix(merkleize(pack(b), (limit * 8 + 31) // 32), len(vals))
+def clist(roots, limit): return mix(merkleize(roots, limit), len(roots))
+def container(roots): return merkleize(roots)
+def bitvector(s, n): return merkleize(pack(hexb(s)), (n + 255) // 256)
+def bitlist(s, n):
+    b = bytearray(hexb(s)); last = b[-1]; bl = last.bit_length() - 1
+    length = (len(b) - 1) * 8 + bl
+    b[-1] = last & ~(1 << bl)
+    if b[-1] == 0 and bl == 0: b = b[:-1]
+    return mix(merkleize(pack(bytes(b)), (n + 255) // 256), length)
+
+def header(x): return container([u64(x["slot"]), u64(x["proposer_index"]), hexb(x["parent_root"]), hexb(x["state_root"]), hexb(x["body_root"])])
+def checkpoint(x): return container([u64(x["epoch"]), hexb(x["root"])])
+def att_data(x): return container([u64(x["slot"]), u64(x["index"]), hexb(x["beacon_block_root"]), checkpoint(x["source"]), checkpoint(x["target"])])
+def signed_header(x): return container([header(x["message"]), bytes_vec(x["signature"])])
+def indexed(x): return container([u64_list(x["attesting_indices"], 2048), att_data(x["data"]), bytes_vec(x["signature"])])
+def attestation(x): return container([bitlist(x["aggregation_bits"], 2048), att_data(x["data"]), bytes_vec(x["signature"])])
+def deposit(x):
+    d = x["data"]
+    return container([merkleize([hexb(p) for p in x["proof"]], 33), container([bytes_vec(d["pubkey"]), hexb(d["withdrawal_credentials"]), u64(d["amount"]), bytes_vec(d["signature"])])])
+def exit_(x): return container([container([u64(x["message"]["epoch"]), u64(x["message"]["validator_index"])]), bytes_vec(x["signature"])])
+def withdrawal(x): return container([u64(x["index"]), u64(x["validator_index"]), bytes_vec(x["address"]), u64(x["amount"])])
+def bls_change(x):
+    m = x["message"]
+    return container([container([u64(m["validator_index"]), bytes_vec(m["from_bls_pubkey"]), bytes_vec(m["to_execution_address"])]), bytes_vec(x["signature"])])
+
+# minimal preset
+SYNC, WITHDRAWALS, COMMITMENTS = 32, 4, 16
+FORKS = ["PHASE0", "ALTAIR", "BELLATRIX", "CAPELLA", "DENEB"]
+
+def payload_fields(p, fork):
+    f = [hexb(p["parent_hash"]), bytes_vec(p["fee_recipient"]), hexb(p["state_root"]), hexb(p["receipts_root"]),
+         bytes_vec(p["logs_bloom"]), hexb(p["prev_randao"]), u64(p["block_number"]), u64(p["gas_limit"]),
+         u64(p["gas_used"]), u64(p["timestamp"]), byte_list(p["extra_data"], 32), u256(p["base_fee_per_gas"]),
+         hexb(p["block_hash"])]
+    if "transactions_root" in p:
+        f.append(hexb(p["transactions_root"]))
+        if fork >= 3: f.append(hexb(p["withdrawals_root"]))
+    else:
+        f.append(clist([byte_list(t, 2**30) for t in p["transactions"]], 2**20))
+        if fork >= 3: f.append(clist([withdrawal(w) for w in p["withdrawals"]], WITHDRAWALS))
+    if fork >= 4: f += [u64(p["blob_gas_used"]), u64(p["excess_blob_gas"])]
+    return f
+
+def body_root(b, fork):
+    f = [bytes_vec(b["randao_reveal"]),
+         container([hexb(b["eth1_data"]["deposit_root"]), u64(b["eth1_data"]["deposit_count"]), hexb(b["eth1_data"]["block_hash"])]),
+         hexb(b["graffiti"]),
+         clist([container([signed_header(s["signed_header_1"]), signed_header(s["signed_header_2"])]) for s in b["proposer_slashings"]], 16),
+         clist([container([indexed(s["attestation_1"]), indexed(s["attestation_2"])]) for s in b["attester_slashings"]], 2),
+         clist([attestation(a) for a in b["attestations"]], 128),
+         clist([deposit(d) for d in b["deposits"]], 16),
+         clist([exit_(e) for e in b["voluntary_exits"]], 16)]
+    if fork >= 1: f.append(container([bitvector(b["sync_aggregate"]["sync_committee_bits"], SYNC), bytes_vec(b["sync_aggregate"]["sync_committee_signature"])]))
+    if fork >= 2: f.append(container(payload_fields(b.get("execution_payload") or b["execution_payload_header"], fork)))
+    if fork >= 3: f.append(clist([bls_change(c) for c in b["bls_to_execution_changes"]], 16))
+    if fork >= 4: f.append(clist([bytes_vec(c) for c in b["blob_kzg_commitments"]], COMMITMENTS))
+    return container(f)
+
+def signing_root(obj_root, fork_info, domain_type):
+    fork_data = container([hexb(fork_info["fork"]["current_version"]) + b"\0" * 28, hexb(fork_info["genesis_validators_root"])])
+    return container([obj_root, domain_type + fork_data[:28]])
+
+if __name__ == "__main__":
+    DOMAIN_BEACON_PROPOSER = b"\0\0\0\0"
+    fixtures = sorted(glob.glob(os.path.join(os.path.dirname(os.path.abspath(__file__)), "block_v2_*.json")))
+    for path in fixtures:
+        request = json.load(open(path))
+        beacon_block = request["beacon_block"]
+        block = beacon_block["block"]
+        root = body_root(block["body"], FORKS.index(beacon_block["version"]))
+        block_header = dict({k: block[k] for k in ("slot", "proposer_index", "parent_root", "state_root")}, body_root="0x" + root.hex())
+        computed = "0x" + signing_root(header(block_header), request["fork_info"], DOMAIN_BEACON_PROPOSER).hex()
+        assert computed == request["signingRoot"], f"{path}: {computed} != {request['signingRoot']}"
+        print(f"{os.path.basename(path)}: body_root 0x{root.hex()} signingRoot {computed}")
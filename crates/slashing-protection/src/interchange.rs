@@ -0,0 +1,50 @@
+//! Slashing protection interchange format as defined by [EIP-3076][1].
+//!
+//![1]: https://eips.ethereum.org/EIPS/eip-3076
+
+use crate::Hash256;
+use bls::PublicKey;
+use serde::{Deserialize, Serialize};
+
+/// The only interchange format version supported.
+pub const INTERCHANGE_FORMAT_VERSION: u64 = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interchange {
+    pub metadata: InterchangeMetadata,
+    pub data: Vec<InterchangeData>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterchangeMetadata {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub interchange_format_version: u64,
+    pub genesis_validators_root: Hash256,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterchangeData {
+    pub pubkey: PublicKey,
+    #[serde(default)]
+    pub signed_blocks: Vec<SignedBlock>,
+    #[serde(default)]
+    pub signed_attestations: Vec<SignedAttestation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedBlock {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub slot: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing_root: Option<Hash256>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedAttestation {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub source_epoch: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub target_epoch: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing_root: Option<Hash256>,
+}
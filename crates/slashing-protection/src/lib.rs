@@ -0,0 +1,538 @@
+//! Slashing protection database. Records the blocks and attestations signed by each validator
+//! and refuses to sign slashable messages, as described in the [validator guide][1].
+//! Signing history can be migrated between clients with the [EIP-3076][2] interchange format.
+//!
+//![1]: https://github.com/ethereum/consensus-specs/blob/dev/specs/phase0/validator.md#how-to-avoid-slashing
+//![2]: https://eips.ethereum.org/EIPS/eip-3076
+
+pub mod interchange;
+#[cfg(test)]
+mod tests;
+
+use crate::interchange::*;
+use bls::PublicKey;
+use rusqlite::{params, Connection, OptionalExtension, Transaction, TransactionBehavior};
+use std::path::Path;
+use std::sync::Mutex;
+use thiserror::Error;
+
+pub type Hash256 = primitive_types::H256;
+
+// A NULL signing root is an unknown signing root, it never matches a message to sign.
+// Low watermarks are raised by imports: messages at or below them are refused, unless they
+// repeat a recorded message.
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS validators (
+        id INTEGER PRIMARY KEY,
+        pubkey BLOB NOT NULL UNIQUE
+    );
+    CREATE TABLE IF NOT EXISTS signed_blocks (
+        validator_id INTEGER NOT NULL REFERENCES validators(id),
+        slot INTEGER NOT NULL,
+        signing_root BLOB,
+        UNIQUE (validator_id, slot)
+    );
+    CREATE TABLE IF NOT EXISTS signed_attestations (
+        validator_id INTEGER NOT NULL REFERENCES validators(id),
+        source_epoch INTEGER NOT NULL,
+        target_epoch INTEGER NOT NULL,
+        signing_root BLOB,
+        UNIQUE (validator_id, target_epoch)
+    );
+    CREATE TABLE IF NOT EXISTS low_watermarks (
+        validator_id INTEGER PRIMARY KEY REFERENCES validators(id),
+        block_slot INTEGER,
+        source_epoch INTEGER,
+        target_epoch INTEGER
+    );
+";
+
+/// Signing root as stored in the database, `None` when unknown.
+type StoredRoot = Option<Vec<u8>>;
+
+#[derive(Error, Debug)]
+pub enum SlashingProtectionError {
+    #[error("Block proposal at slot {slot} conflicts with a previously signed block")]
+    DoubleBlockProposal { slot: u64 },
+    #[error("Block proposal at slot {slot} is not above the imported slot {min_slot}")]
+    BlockSlotTooLow { slot: u64, min_slot: u64 },
+    #[error("Attestation with target epoch {target_epoch} conflicts with a previously signed attestation")]
+    DoubleVote { target_epoch: u64 },
+    #[error("Attestation (source epoch {source_epoch}, target epoch {target_epoch}) surrounds a previously signed attestation")]
+    SurroundingVote {
+        source_epoch: u64,
+        target_epoch: u64,
+    },
+    #[error("Attestation (source epoch {source_epoch}, target epoch {target_epoch}) is surrounded by a previously signed attestation")]
+    SurroundedVote {
+        source_epoch: u64,
+        target_epoch: u64,
+    },
+    #[error("Attestation (source epoch {source_epoch}, target epoch {target_epoch}) is below the imported epochs")]
+    AttestationEpochTooLow {
+        source_epoch: u64,
+        target_epoch: u64,
+    },
+    #[error("Attestation source epoch {source_epoch} is greater than target epoch {target_epoch}")]
+    InvalidAttestation {
+        source_epoch: u64,
+        target_epoch: u64,
+    },
+    #[error("Interchange format version {0} is not supported")]
+    UnsupportedInterchangeVersion(u64),
+    #[error("Interchange genesis_validators_root {actual:?} does not match the network genesis_validators_root {expected:?}")]
+    GenesisValidatorsRootMismatch { expected: Hash256, actual: Hash256 },
+    #[error("Value {0} is out of range for the slashing protection database")]
+    ValueOutOfRange(u64),
+    #[error("Slashing protection database error: {0}")]
+    Database(#[from] rusqlite::Error),
+}
+
+impl SlashingProtectionError {
+    /// `true` if the message was refused because signing it could be slashable, `false` if
+    /// the check itself failed.
+    pub fn is_slashable(&self) -> bool {
+        matches!(
+            self,
+            SlashingProtectionError::DoubleBlockProposal { .. }
+                | SlashingProtectionError::BlockSlotTooLow { .. }
+                | SlashingProtectionError::DoubleVote { .. }
+                | SlashingProtectionError::SurroundingVote { .. }
+                | SlashingProtectionError::SurroundedVote { .. }
+                | SlashingProtectionError::AttestationEpochTooLow { .. }
+                | SlashingProtectionError::InvalidAttestation { .. }
+        )
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct LowWatermarks {
+    block_slot: Option<u64>,
+    source_epoch: Option<u64>,
+    target_epoch: Option<u64>,
+}
+
+/// SQLite backed slashing protection database.
+pub struct SlashingProtection {
+    conn: Mutex<Connection>,
+}
+
+impl SlashingProtection {
+    /// Open (or create) the database at `path`.
+    pub fn open(path: &Path) -> Result<Self, SlashingProtectionError> {
+        SlashingProtection::init(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self, SlashingProtectionError> {
+        SlashingProtection::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self, SlashingProtectionError> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(SlashingProtection {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Check that signing a block at `slot` is safe and record it. Signing the same block
+    /// (same signing root) again is allowed.
+    pub fn check_and_insert_block_proposal(
+        &self,
+        pubkey: &PublicKey,
+        slot: u64,
+        signing_root: &Hash256,
+    ) -> Result<(), SlashingProtectionError> {
+        let mut conn = self.conn.lock().expect("slashing protection lock poisoned");
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Exclusive)?;
+        let validator_id = validator_id(&tx, pubkey)?;
+        let sql_slot = to_sql(slot)?;
+
+        match signed_block_root(&tx, validator_id, sql_slot)? {
+            Some(existing_root) if is_same_root(&existing_root, signing_root) => return Ok(()),
+            Some(_) => return Err(SlashingProtectionError::DoubleBlockProposal { slot }),
+            None => {}
+        }
+
+        if let Some(min_slot) = low_watermarks(&tx, validator_id)?.block_slot {
+            if slot <= min_slot {
+                return Err(SlashingProtectionError::BlockSlotTooLow { slot, min_slot });
+            }
+        }
+
+        tx.execute(
+            "INSERT INTO signed_blocks (validator_id, slot, signing_root) VALUES (?1, ?2, ?3)",
+            params![validator_id, sql_slot, signing_root.as_bytes()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Check that signing an attestation from `source_epoch` to `target_epoch` is not a double
+    /// or surround vote, and record it. Signing the same attestation (same signing root) again
+    /// is allowed.
+    pub fn check_and_insert_attestation(
+        &self,
+        pubkey: &PublicKey,
+        source_epoch: u64,
+        target_epoch: u64,
+        signing_root: &Hash256,
+    ) -> Result<(), SlashingProtectionError> {
+        if source_epoch > target_epoch {
+            return Err(SlashingProtectionError::InvalidAttestation {
+                source_epoch,
+                target_epoch,
+            });
+        }
+
+        let mut conn = self.conn.lock().expect("slashing protection lock poisoned");
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Exclusive)?;
+        let validator_id = validator_id(&tx, pubkey)?;
+        let sql_source = to_sql(source_epoch)?;
+        let sql_target = to_sql(target_epoch)?;
+
+        match signed_attestation(&tx, validator_id, sql_target)? {
+            Some((_, existing_root)) if is_same_root(&existing_root, signing_root) => return Ok(()),
+            Some(_) => return Err(SlashingProtectionError::DoubleVote { target_epoch }),
+            None => {}
+        }
+
+        let watermarks = low_watermarks(&tx, validator_id)?;
+        let below_source = watermarks
+            .source_epoch
+            .map_or(false, |min_source| source_epoch < min_source);
+        let below_target = watermarks
+            .target_epoch
+            .map_or(false, |min_target| target_epoch <= min_target);
+        if below_source || below_target {
+            return Err(SlashingProtectionError::AttestationEpochTooLow {
+                source_epoch,
+                target_epoch,
+            });
+        }
+
+        let surrounding: bool = tx.query_row(
+            "SELECT EXISTS (SELECT 1 FROM signed_attestations
+             WHERE validator_id = ?1 AND source_epoch > ?2 AND target_epoch < ?3)",
+            params![validator_id, sql_source, sql_target],
+            |row| row.get(0),
+        )?;
+        if surrounding {
+            return Err(SlashingProtectionError::SurroundingVote {
+                source_epoch,
+                target_epoch,
+            });
+        }
+
+        let surrounded: bool = tx.query_row(
+            "SELECT EXISTS (SELECT 1 FROM signed_attestations
+             WHERE validator_id = ?1 AND source_epoch < ?2 AND target_epoch > ?3)",
+            params![validator_id, sql_source, sql_target],
+            |row| row.get(0),
+        )?;
+        if surrounded {
+            return Err(SlashingProtectionError::SurroundedVote {
+                source_epoch,
+                target_epoch,
+            });
+        }
+
+        tx.execute(
+            "INSERT INTO signed_attestations (validator_id, source_epoch, target_epoch, signing_root)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![validator_id, sql_source, sql_target, signing_root.as_bytes()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Import an EIP-3076 interchange, merging it with the existing records. Records which
+    /// conflict with existing ones lose their signing root, and the low watermarks are raised
+    /// to the highest imported slot and epochs, so nothing at or below them can be signed.
+    /// The import is atomic.
+    pub fn import_interchange(
+        &self,
+        interchange: &Interchange,
+        genesis_validators_root: &Hash256,
+    ) -> Result<(), SlashingProtectionError> {
+        check_interchange_metadata(&interchange.metadata, genesis_validators_root)?;
+
+        let mut conn = self.conn.lock().expect("slashing protection lock poisoned");
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Exclusive)?;
+        for data in &interchange.data {
+            import_validator_data(&tx, data)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Export the signing history as an EIP-3076 interchange. When `pubkeys` is provided only
+    /// the history of those validators is exported.
+    pub fn export_interchange(
+        &self,
+        genesis_validators_root: &Hash256,
+        pubkeys: Option<&[PublicKey]>,
+    ) -> Result<Interchange, SlashingProtectionError> {
+        let conn = self.conn.lock().expect("slashing protection lock poisoned");
+
+        let mut validators = Vec::new();
+        {
+            let mut stmt = conn.prepare("SELECT id, pubkey FROM validators ORDER BY id")?;
+            let mut rows = stmt.query([])?;
+            while let Some(row) = rows.next()? {
+                let validator_id: i64 = row.get(0)?;
+                let pubkey_bytes: Vec<u8> = row.get(1)?;
+                let Ok(pubkey) = PublicKey::from_bytes(&pubkey_bytes) else {
+                    continue;
+                };
+                if pubkeys.map_or(true, |pubkeys| pubkeys.contains(&pubkey)) {
+                    validators.push((validator_id, pubkey));
+                }
+            }
+        }
+
+        let mut data = Vec::with_capacity(validators.len());
+        for (validator_id, pubkey) in validators {
+            data.push(InterchangeData {
+                pubkey,
+                signed_blocks: export_signed_blocks(&conn, validator_id)?,
+                signed_attestations: export_signed_attestations(&conn, validator_id)?,
+            });
+        }
+
+        Ok(Interchange {
+            metadata: InterchangeMetadata {
+                interchange_format_version: INTERCHANGE_FORMAT_VERSION,
+                genesis_validators_root: *genesis_validators_root,
+            },
+            data,
+        })
+    }
+}
+
+fn check_interchange_metadata(
+    metadata: &InterchangeMetadata,
+    genesis_validators_root: &Hash256,
+) -> Result<(), SlashingProtectionError> {
+    if metadata.interchange_format_version != INTERCHANGE_FORMAT_VERSION {
+        return Err(SlashingProtectionError::UnsupportedInterchangeVersion(
+            metadata.interchange_format_version,
+        ));
+    }
+    if metadata.genesis_validators_root != *genesis_validators_root {
+        return Err(SlashingProtectionError::GenesisValidatorsRootMismatch {
+            expected: *genesis_validators_root,
+            actual: metadata.genesis_validators_root,
+        });
+    }
+    Ok(())
+}
+
+fn import_validator_data(
+    tx: &Transaction,
+    data: &InterchangeData,
+) -> Result<(), SlashingProtectionError> {
+    let validator_id = validator_id(tx, &data.pubkey)?;
+    let mut watermarks = low_watermarks(tx, validator_id)?;
+
+    for block in &data.signed_blocks {
+        let sql_slot = to_sql(block.slot)?;
+        match signed_block_root(tx, validator_id, sql_slot)? {
+            Some(existing_root) if is_same_imported_root(&existing_root, &block.signing_root) => {}
+            Some(_) => {
+                tx.execute(
+                    "UPDATE signed_blocks SET signing_root = NULL
+                     WHERE validator_id = ?1 AND slot = ?2",
+                    params![validator_id, sql_slot],
+                )?;
+            }
+            None => {
+                tx.execute(
+                    "INSERT INTO signed_blocks (validator_id, slot, signing_root)
+                     VALUES (?1, ?2, ?3)",
+                    params![
+                        validator_id,
+                        sql_slot,
+                        block.signing_root.as_ref().map(Hash256::as_bytes)
+                    ],
+                )?;
+            }
+        }
+        watermarks.block_slot = watermarks.block_slot.max(Some(block.slot));
+    }
+
+    for attestation in &data.signed_attestations {
+        let sql_source = to_sql(attestation.source_epoch)?;
+        let sql_target = to_sql(attestation.target_epoch)?;
+        match signed_attestation(tx, validator_id, sql_target)? {
+            Some((existing_source, existing_root))
+                if existing_source == sql_source
+                    && is_same_imported_root(&existing_root, &attestation.signing_root) => {}
+            Some(_) => {
+                tx.execute(
+                    "UPDATE signed_attestations SET signing_root = NULL
+                     WHERE validator_id = ?1 AND target_epoch = ?2",
+                    params![validator_id, sql_target],
+                )?;
+            }
+            None => {
+                tx.execute(
+                    "INSERT INTO signed_attestations
+                     (validator_id, source_epoch, target_epoch, signing_root)
+                     VALUES (?1, ?2, ?3, ?4)",
+                    params![
+                        validator_id,
+                        sql_source,
+                        sql_target,
+                        attestation.signing_root.as_ref().map(Hash256::as_bytes)
+                    ],
+                )?;
+            }
+        }
+        watermarks.source_epoch = watermarks.source_epoch.max(Some(attestation.source_epoch));
+        watermarks.target_epoch = watermarks.target_epoch.max(Some(attestation.target_epoch));
+    }
+
+    tx.execute(
+        "INSERT OR REPLACE INTO low_watermarks (validator_id, block_slot, source_epoch, target_epoch)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![
+            validator_id,
+            watermarks.block_slot.map(to_sql).transpose()?,
+            watermarks.source_epoch.map(to_sql).transpose()?,
+            watermarks.target_epoch.map(to_sql).transpose()?,
+        ],
+    )?;
+    Ok(())
+}
+
+fn export_signed_blocks(
+    conn: &Connection,
+    validator_id: i64,
+) -> Result<Vec<SignedBlock>, SlashingProtectionError> {
+    let mut stmt = conn.prepare(
+        "SELECT slot, signing_root FROM signed_blocks WHERE validator_id = ?1 ORDER BY slot",
+    )?;
+    let mut rows = stmt.query(params![validator_id])?;
+    let mut signed_blocks = Vec::new();
+    while let Some(row) = rows.next()? {
+        signed_blocks.push(SignedBlock {
+            slot: from_sql(row.get(0)?),
+            signing_root: to_root(row.get(1)?),
+        });
+    }
+    Ok(signed_blocks)
+}
+
+fn export_signed_attestations(
+    conn: &Connection,
+    validator_id: i64,
+) -> Result<Vec<SignedAttestation>, SlashingProtectionError> {
+    let mut stmt = conn.prepare(
+        "SELECT source_epoch, target_epoch, signing_root FROM signed_attestations
+         WHERE validator_id = ?1 ORDER BY target_epoch",
+    )?;
+    let mut rows = stmt.query(params![validator_id])?;
+    let mut signed_attestations = Vec::new();
+    while let Some(row) = rows.next()? {
+        signed_attestations.push(SignedAttestation {
+            source_epoch: from_sql(row.get(0)?),
+            target_epoch: from_sql(row.get(1)?),
+            signing_root: to_root(row.get(2)?),
+        });
+    }
+    Ok(signed_attestations)
+}
+
+/// Id of the validator, registering it on first use.
+fn validator_id(tx: &Transaction, pubkey: &PublicKey) -> Result<i64, SlashingProtectionError> {
+    let pubkey_bytes = pubkey.compress();
+    tx.execute(
+        "INSERT OR IGNORE INTO validators (pubkey) VALUES (?1)",
+        params![&pubkey_bytes[..]],
+    )?;
+    Ok(tx.query_row(
+        "SELECT id FROM validators WHERE pubkey = ?1",
+        params![&pubkey_bytes[..]],
+        |row| row.get(0),
+    )?)
+}
+
+/// Signing root of the block recorded at `slot`, if any.
+fn signed_block_root(
+    tx: &Transaction,
+    validator_id: i64,
+    slot: i64,
+) -> Result<Option<StoredRoot>, SlashingProtectionError> {
+    Ok(tx
+        .query_row(
+            "SELECT signing_root FROM signed_blocks WHERE validator_id = ?1 AND slot = ?2",
+            params![validator_id, slot],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+/// Source epoch and signing root of the attestation recorded for `target_epoch`, if any.
+fn signed_attestation(
+    tx: &Transaction,
+    validator_id: i64,
+    target_epoch: i64,
+) -> Result<Option<(i64, StoredRoot)>, SlashingProtectionError> {
+    Ok(tx
+        .query_row(
+            "SELECT source_epoch, signing_root FROM signed_attestations
+             WHERE validator_id = ?1 AND target_epoch = ?2",
+            params![validator_id, target_epoch],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?)
+}
+
+fn low_watermarks(
+    tx: &Transaction,
+    validator_id: i64,
+) -> Result<LowWatermarks, SlashingProtectionError> {
+    let watermarks = tx
+        .query_row(
+            "SELECT block_slot, source_epoch, target_epoch FROM low_watermarks
+             WHERE validator_id = ?1",
+            params![validator_id],
+            |row| {
+                Ok(LowWatermarks {
+                    block_slot: row.get::<_, Option<i64>>(0)?.map(from_sql),
+                    source_epoch: row.get::<_, Option<i64>>(1)?.map(from_sql),
+                    target_epoch: row.get::<_, Option<i64>>(2)?.map(from_sql),
+                })
+            },
+        )
+        .optional()?;
+    Ok(watermarks.unwrap_or_default())
+}
+
+fn is_same_root(existing_root: &StoredRoot, signing_root: &Hash256) -> bool {
+    existing_root.as_deref() == Some(signing_root.as_bytes())
+}
+
+/// Unknown signing roots are never the same.
+fn is_same_imported_root(existing_root: &StoredRoot, signing_root: &Option<Hash256>) -> bool {
+    match signing_root {
+        Some(signing_root) => is_same_root(existing_root, signing_root),
+        None => false,
+    }
+}
+
+fn to_root(bytes: StoredRoot) -> Option<Hash256> {
+    bytes
+        .filter(|bytes| bytes.len() == 32)
+        .map(|bytes| Hash256::from_slice(&bytes))
+}
+
+/// SQLite integers are signed 64 bits.
+fn to_sql(value: u64) -> Result<i64, SlashingProtectionError> {
+    i64::try_from(value).map_err(|_| SlashingProtectionError::ValueOutOfRange(value))
+}
+
+/// Values are only stored through [`to_sql`], they are never negative.
+fn from_sql(value: i64) -> u64 {
+    value as u64
+}
@@ -0,0 +1,397 @@
+use super::*;
+
+const PUBKEY: &str = "0x9612d7a727c9d0a22e185a1c768478dfe919cada9266988cb32359c11f2b7b27f4ae4040902382ae2910c15e2b420d07";
+const OTHER_PUBKEY: &str = "0xa491d1b0ecd9bb917989f0e74f0dea0422eac4a873e5e2644f368dffb9a6e20fd6e10c1b77654d067c0618f6e5a7f79a";
+
+fn pubkey() -> PublicKey {
+    PublicKey::from_hex(PUBKEY).unwrap()
+}
+
+fn root(byte: u8) -> Hash256 {
+    Hash256::repeat_byte(byte)
+}
+
+#[test]
+fn block_proposal_is_recorded_and_double_proposal_refused() {
+    let db = SlashingProtection::open_in_memory().unwrap();
+
+    db.check_and_insert_block_proposal(&pubkey(), 10, &root(1))
+        .unwrap();
+    // same block can be signed again
+    db.check_and_insert_block_proposal(&pubkey(), 10, &root(1))
+        .unwrap();
+    let err = db
+        .check_and_insert_block_proposal(&pubkey(), 10, &root(2))
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        SlashingProtectionError::DoubleBlockProposal { slot: 10 }
+    ));
+    assert!(err.is_slashable());
+
+    db.check_and_insert_block_proposal(&pubkey(), 11, &root(2))
+        .unwrap();
+}
+
+#[test]
+fn validators_are_protected_independently() {
+    let db = SlashingProtection::open_in_memory().unwrap();
+    let other_pubkey = PublicKey::from_hex(OTHER_PUBKEY).unwrap();
+
+    db.check_and_insert_block_proposal(&pubkey(), 10, &root(1))
+        .unwrap();
+    db.check_and_insert_block_proposal(&other_pubkey, 10, &root(2))
+        .unwrap();
+    db.check_and_insert_attestation(&pubkey(), 1, 2, &root(1))
+        .unwrap();
+    db.check_and_insert_attestation(&other_pubkey, 1, 2, &root(2))
+        .unwrap();
+}
+
+#[test]
+fn double_vote_is_refused() {
+    let db = SlashingProtection::open_in_memory().unwrap();
+
+    db.check_and_insert_attestation(&pubkey(), 1, 2, &root(1))
+        .unwrap();
+    db.check_and_insert_attestation(&pubkey(), 1, 2, &root(1))
+        .unwrap();
+    let err = db
+        .check_and_insert_attestation(&pubkey(), 0, 2, &root(2))
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        SlashingProtectionError::DoubleVote { target_epoch: 2 }
+    ));
+}
+
+#[test]
+fn surrounding_vote_is_refused() {
+    let db = SlashingProtection::open_in_memory().unwrap();
+
+    db.check_and_insert_attestation(&pubkey(), 3, 4, &root(1))
+        .unwrap();
+    let err = db
+        .check_and_insert_attestation(&pubkey(), 2, 5, &root(2))
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        SlashingProtectionError::SurroundingVote {
+            source_epoch: 2,
+            target_epoch: 5
+        }
+    ));
+}
+
+#[test]
+fn surrounded_vote_is_refused() {
+    let db = SlashingProtection::open_in_memory().unwrap();
+
+    db.check_and_insert_attestation(&pubkey(), 2, 5, &root(1))
+        .unwrap();
+    let err = db
+        .check_and_insert_attestation(&pubkey(), 3, 4, &root(2))
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        SlashingProtectionError::SurroundedVote {
+            source_epoch: 3,
+            target_epoch: 4
+        }
+    ));
+}
+
+#[test]
+fn non_slashable_attestations_are_allowed() {
+    let db = SlashingProtection::open_in_memory().unwrap();
+
+    db.check_and_insert_attestation(&pubkey(), 2, 5, &root(1))
+        .unwrap();
+    // same source, later target
+    db.check_and_insert_attestation(&pubkey(), 2, 6, &root(2))
+        .unwrap();
+    // overlapping without surrounding
+    db.check_and_insert_attestation(&pubkey(), 3, 7, &root(3))
+        .unwrap();
+    // earlier target with a non surrounded range
+    db.check_and_insert_attestation(&pubkey(), 0, 1, &root(4))
+        .unwrap();
+}
+
+#[test]
+fn invalid_attestation_is_refused() {
+    let db = SlashingProtection::open_in_memory().unwrap();
+    let err = db
+        .check_and_insert_attestation(&pubkey(), 5, 4, &root(1))
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        SlashingProtectionError::InvalidAttestation { .. }
+    ));
+}
+
+#[test]
+fn out_of_range_values_are_not_slashable_errors() {
+    let db = SlashingProtection::open_in_memory().unwrap();
+    let err = db
+        .check_and_insert_block_proposal(&pubkey(), u64::MAX, &root(1))
+        .unwrap_err();
+    assert!(matches!(err, SlashingProtectionError::ValueOutOfRange(_)));
+    assert!(!err.is_slashable());
+}
+
+#[test]
+fn records_are_persisted() {
+    let db_dir = tempfile::tempdir().unwrap();
+    let db_path = db_dir.path().join("slashing_protection.sqlite");
+
+    SlashingProtection::open(&db_path)
+        .unwrap()
+        .check_and_insert_block_proposal(&pubkey(), 10, &root(1))
+        .unwrap();
+
+    let db = SlashingProtection::open(&db_path).unwrap();
+    assert!(db
+        .check_and_insert_block_proposal(&pubkey(), 10, &root(2))
+        .is_err());
+}
+
+const GENESIS_VALIDATORS_ROOT: &str =
+    "0x04700007fabc8282644aed6d1c7c9e21d38a03a0c4ba193f3afe428824b3a673";
+
+fn genesis_validators_root() -> Hash256 {
+    GENESIS_VALIDATORS_ROOT.parse().unwrap()
+}
+
+fn interchange_json(signed_blocks: &str, signed_attestations: &str) -> String {
+    format!(
+        r#"{{
+            "metadata": {{
+                "interchange_format_version": "5",
+                "genesis_validators_root": "{GENESIS_VALIDATORS_ROOT}"
+            }},
+            "data": [{{
+                "pubkey": "{PUBKEY}",
+                "signed_blocks": {signed_blocks},
+                "signed_attestations": {signed_attestations}
+            }}]
+        }}"#
+    )
+}
+
+fn import(db: &SlashingProtection, json: &str) -> Result<(), SlashingProtectionError> {
+    let interchange: Interchange = serde_json::from_str(json).unwrap();
+    db.import_interchange(&interchange, &genesis_validators_root())
+}
+
+#[test]
+fn interchange_round_trips() {
+    let db = SlashingProtection::open_in_memory().unwrap();
+    db.check_and_insert_block_proposal(&pubkey(), 10, &root(1))
+        .unwrap();
+    db.check_and_insert_attestation(&pubkey(), 2, 3, &root(2))
+        .unwrap();
+
+    let exported = db
+        .export_interchange(&genesis_validators_root(), None)
+        .unwrap();
+    assert_eq!(exported.metadata.interchange_format_version, 5);
+    assert_eq!(exported.data.len(), 1);
+    assert_eq!(exported.data[0].pubkey, pubkey());
+    assert_eq!(
+        exported.data[0].signed_blocks,
+        vec![SignedBlock {
+            slot: 10,
+            signing_root: Some(root(1))
+        }]
+    );
+
+    let json = serde_json::to_string(&exported).unwrap();
+    assert!(json.contains(r#""interchange_format_version":"5""#));
+    assert!(json.contains(r#""slot":"10""#));
+
+    let other_db = SlashingProtection::open_in_memory().unwrap();
+    import(&other_db, &json).unwrap();
+    assert_eq!(
+        other_db
+            .export_interchange(&genesis_validators_root(), None)
+            .unwrap(),
+        exported
+    );
+    // the imported history is enforced
+    assert!(other_db
+        .check_and_insert_block_proposal(&pubkey(), 10, &root(3))
+        .is_err());
+    other_db
+        .check_and_insert_attestation(&pubkey(), 2, 3, &root(2))
+        .unwrap();
+}
+
+#[test]
+fn export_is_filtered_by_pubkeys() {
+    let db = SlashingProtection::open_in_memory().unwrap();
+    let other_pubkey = PublicKey::from_hex(OTHER_PUBKEY).unwrap();
+    db.check_and_insert_block_proposal(&pubkey(), 10, &root(1))
+        .unwrap();
+    db.check_and_insert_block_proposal(&other_pubkey, 10, &root(1))
+        .unwrap();
+
+    let exported = db
+        .export_interchange(
+            &genesis_validators_root(),
+            Some(std::slice::from_ref(&other_pubkey)),
+        )
+        .unwrap();
+    assert_eq!(exported.data.len(), 1);
+    assert_eq!(exported.data[0].pubkey, other_pubkey);
+}
+
+#[test]
+fn import_raises_low_watermarks() {
+    let db = SlashingProtection::open_in_memory().unwrap();
+    import(
+        &db,
+        &interchange_json(
+            r#"[{"slot": "10"}]"#,
+            r#"[{"source_epoch": "5", "target_epoch": "6"}]"#,
+        ),
+    )
+    .unwrap();
+
+    // blocks at or below the imported slot are refused, even for unseen slots
+    let err = db
+        .check_and_insert_block_proposal(&pubkey(), 9, &root(1))
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        SlashingProtectionError::BlockSlotTooLow {
+            slot: 9,
+            min_slot: 10
+        }
+    ));
+    assert!(err.is_slashable());
+    // an unknown signing root never matches
+    assert!(db
+        .check_and_insert_block_proposal(&pubkey(), 10, &root(1))
+        .is_err());
+    db.check_and_insert_block_proposal(&pubkey(), 11, &root(1))
+        .unwrap();
+
+    assert!(matches!(
+        db.check_and_insert_attestation(&pubkey(), 4, 7, &root(1)),
+        Err(SlashingProtectionError::AttestationEpochTooLow { .. })
+    ));
+    assert!(matches!(
+        db.check_and_insert_attestation(&pubkey(), 5, 5, &root(1)),
+        Err(SlashingProtectionError::AttestationEpochTooLow { .. })
+    ));
+    db.check_and_insert_attestation(&pubkey(), 5, 7, &root(1))
+        .unwrap();
+}
+
+#[test]
+fn import_merges_conservatively() {
+    let db = SlashingProtection::open_in_memory().unwrap();
+    db.check_and_insert_block_proposal(&pubkey(), 20, &root(1))
+        .unwrap();
+    db.check_and_insert_attestation(&pubkey(), 8, 9, &root(1))
+        .unwrap();
+
+    // older history and a conflicting block at slot 20
+    import(
+        &db,
+        &interchange_json(
+            &format!(
+                r#"[{{"slot": "5"}}, {{"slot": "20", "signing_root": "{:?}"}}]"#,
+                root(2)
+            ),
+            r#"[{"source_epoch": "1", "target_epoch": "2"}]"#,
+        ),
+    )
+    .unwrap();
+
+    let exported = db
+        .export_interchange(&genesis_validators_root(), None)
+        .unwrap();
+    assert_eq!(
+        exported.data[0].signed_blocks,
+        vec![
+            SignedBlock {
+                slot: 5,
+                signing_root: None
+            },
+            SignedBlock {
+                slot: 20,
+                signing_root: None
+            },
+        ]
+    );
+    assert_eq!(exported.data[0].signed_attestations.len(), 2);
+
+    // the watermark stays at the highest known slot
+    assert!(db
+        .check_and_insert_block_proposal(&pubkey(), 20, &root(1))
+        .is_err());
+    // local history above the imported watermarks still applies
+    assert!(matches!(
+        db.check_and_insert_attestation(&pubkey(), 8, 9, &root(2)),
+        Err(SlashingProtectionError::DoubleVote { target_epoch: 9 })
+    ));
+    db.check_and_insert_attestation(&pubkey(), 9, 10, &root(2))
+        .unwrap();
+}
+
+#[test]
+fn import_is_idempotent() {
+    let db = SlashingProtection::open_in_memory().unwrap();
+    let json = interchange_json(
+        &format!(r#"[{{"slot": "10", "signing_root": "{:?}"}}]"#, root(1)),
+        "[]",
+    );
+    import(&db, &json).unwrap();
+    import(&db, &json).unwrap();
+
+    let exported = db
+        .export_interchange(&genesis_validators_root(), None)
+        .unwrap();
+    assert_eq!(
+        exported.data[0].signed_blocks,
+        vec![SignedBlock {
+            slot: 10,
+            signing_root: Some(root(1))
+        }]
+    );
+    db.check_and_insert_block_proposal(&pubkey(), 10, &root(1))
+        .unwrap();
+}
+
+#[test]
+fn import_with_other_genesis_validators_root_is_refused() {
+    let db = SlashingProtection::open_in_memory().unwrap();
+    let interchange: Interchange =
+        serde_json::from_str(&interchange_json(r#"[{"slot": "10"}]"#, "[]")).unwrap();
+
+    let err = db.import_interchange(&interchange, &root(9)).unwrap_err();
+    assert!(matches!(
+        err,
+        SlashingProtectionError::GenesisValidatorsRootMismatch { .. }
+    ));
+    assert!(!err.is_slashable());
+    // nothing was imported
+    db.check_and_insert_block_proposal(&pubkey(), 10, &root(1))
+        .unwrap();
+}
+
+#[test]
+fn import_of_unsupported_version_is_refused() {
+    let db = SlashingProtection::open_in_memory().unwrap();
+    let json = interchange_json("[]", "[]").replace(
+        r#""interchange_format_version": "5""#,
+        r#""interchange_format_version": "4""#,
+    );
+
+    assert!(matches!(
+        import(&db, &json),
+        Err(SlashingProtectionError::UnsupportedInterchangeVersion(4))
+    ));
+}
@@ -16,20 +16,22 @@ use std::fs::File;
 use std::io::prelude::*;
 use std::path::Path;
 
-const PRESETS_MINIMAL: [&str; 5] = [
+const PRESETS_MINIMAL: [&str; 6] = [
     include_str!("../presets/minimal/phase0.yaml"),
     include_str!("../presets/minimal/altair.yaml"),
     include_str!("../presets/minimal/bellatrix.yaml"),
     include_str!("../presets/minimal/capella.yaml"),
     include_str!("../presets/minimal/deneb.yaml"),
+    include_str!("../presets/minimal/electra.yaml"),
 ];
 
-const PRESETS_MAINNET: [&str; 5] = [
+const PRESETS_MAINNET: [&str; 6] = [
     include_str!("../presets/mainnet/phase0.yaml"),
     include_str!("../presets/mainnet/altair.yaml"),
     include_str!("../presets/mainnet/bellatrix.yaml"),
     include_str!("../presets/mainnet/capella.yaml"),
     include_str!("../presets/mainnet/deneb.yaml"),
+    include_str!("../presets/mainnet/electra.yaml"),
 ];
 
 static PREDEFINED_CONFIGS: phf::Map<&'static str, &'static str> = phf::phf_map! {
@@ -37,11 +39,61 @@ static PREDEFINED_CONFIGS: phf::Map<&'static str, &'static str> = phf::phf_map!
     "mainnet" => include_str!("../configs/mainnet/config.yaml")
 };
 
+// genesis_validators_root is only known once the network has started, it is not part of the config
+static GENESIS_VALIDATORS_ROOTS: phf::Map<&'static str, [u8; 32]> = phf::phf_map! {
+    "mainnet" => hex_literal::hex!("4b363db94e286120d76eb905340fdd4e54bfe9f06bf33ff6cf5ad27f511bfe95"),
+};
+
 // use constants instead of deriving from the preset yaml files
 const SYNC_COMMITTEE_SUBNET_COUNT: usize = 4;
 pub const SYNC_COMMITTEE_CONT_SIZE_MAINNET: usize = 512 / SYNC_COMMITTEE_SUBNET_COUNT;
 pub const SYNC_COMMITTEE_CONT_SIZE_MIMIMAL: usize = 32 / SYNC_COMMITTEE_SUBNET_COUNT;
 
+// SSZ limits of the beacon block containers which are the same in the mainnet and minimal presets
+pub const MAX_VALIDATORS_PER_COMMITTEE: usize = 2048;
+pub const MAX_PROPOSER_SLASHINGS: usize = 16;
+pub const MAX_ATTESTER_SLASHINGS: usize = 2;
+pub const MAX_ATTESTATIONS: usize = 128;
+pub const MAX_DEPOSITS: usize = 16;
+pub const MAX_VOLUNTARY_EXITS: usize = 16;
+pub const MAX_BYTES_PER_TRANSACTION: usize = 1_073_741_824;
+pub const MAX_TRANSACTIONS_PER_PAYLOAD: usize = 1_048_576;
+pub const BYTES_PER_LOGS_BLOOM: usize = 256;
+pub const MAX_EXTRA_DATA_BYTES: usize = 32;
+pub const MAX_BLS_TO_EXECUTION_CHANGES: usize = 16;
+
+// SSZ limits of the beacon block containers which differ between the mainnet and minimal presets
+pub const SYNC_COMMITTEE_SIZE_MAINNET: usize = 512;
+pub const SYNC_COMMITTEE_SIZE_MINIMAL: usize = 32;
+pub const MAX_WITHDRAWALS_PER_PAYLOAD_MAINNET: usize = 16;
+pub const MAX_WITHDRAWALS_PER_PAYLOAD_MINIMAL: usize = 4;
+pub const MAX_BLOB_COMMITMENTS_PER_BLOCK_MAINNET: usize = 4096;
+pub const MAX_BLOB_COMMITMENTS_PER_BLOCK_MINIMAL: usize = 16;
+pub const FIELD_ELEMENTS_PER_BLOB_MAINNET: usize = 4096;
+pub const FIELD_ELEMENTS_PER_BLOB_MINIMAL: usize = 4;
+pub const BYTES_PER_FIELD_ELEMENT: usize = 32;
+pub const MAX_COMMITTEES_PER_SLOT_MAINNET: usize = 64;
+pub const MAX_COMMITTEES_PER_SLOT_MINIMAL: usize = 4;
+
+/// Epoch of the forks which are not scheduled.
+pub const FAR_FUTURE_EPOCH: u64 = u64::MAX;
+
+/// Fork of the schedule of a network, activated at `epoch`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScheduledFork {
+    pub name: &'static str,
+    pub version: [u8; 4],
+    pub epoch: u64,
+}
+
+/// `Fork` of the beacon state at an epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fork {
+    pub previous_version: [u8; 4],
+    pub current_version: [u8; 4],
+    pub epoch: u64,
+}
+
 #[derive(Debug, PartialEq, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub struct Spec {
@@ -65,6 +117,11 @@ pub struct Spec {
     capella_fork_epoch: u64,
     deneb_fork_version: u32,
     deneb_fork_epoch: u64,
+    // configs written before electra do not schedule it
+    #[serde(default = "default_electra_fork_version")]
+    electra_fork_version: u32,
+    #[serde(default = "far_future_epoch")]
+    electra_fork_epoch: u64,
     seconds_per_slot: u64,
     seconds_per_eth1_block: u64,
     min_validator_withdrawability_delay: u64,
@@ -136,6 +193,30 @@ pub struct Spec {
     // ../presets/mainnet/deneb.yaml
     field_elements_per_blob: u64,
     max_blobs_per_block: u64,
+    max_blob_commitments_per_block: u64,
+    // ../presets/mainnet/electra.yaml
+    min_activation_balance: u64,
+    max_effective_balance_electra: u64,
+    pending_deposits_limit: u64,
+    pending_partial_withdrawals_limit: u64,
+    pending_consolidations_limit: u64,
+    min_slashing_penalty_quotient_electra: u64,
+    whistleblower_reward_quotient_electra: u64,
+    max_attester_slashings_electra: u64,
+    max_attestations_electra: u64,
+    max_consolidation_requests_per_payload: u64,
+    max_deposit_requests_per_payload: u64,
+    max_withdrawal_requests_per_payload: u64,
+    max_pending_partials_per_withdrawals_sweep: u64,
+    max_pending_deposits_per_epoch: u64,
+}
+
+fn default_electra_fork_version() -> u32 {
+    0x0500_0000
+}
+
+fn far_future_epoch() -> u64 {
+    FAR_FUTURE_EPOCH
 }
 
 impl Spec {
@@ -194,7 +275,185 @@ impl Spec {
         self.genesis_fork_version.to_be_bytes()
     }
 
+    pub fn config_name(&self) -> &str {
+        &self.config_name
+    }
+
+    /// Forks of the network in activation order, starting with the genesis fork at epoch 0.
+    /// Forks at `FAR_FUTURE_EPOCH` are not scheduled and left out.
+    pub fn fork_schedule(&self) -> Vec<ScheduledFork> {
+        [
+            ("phase0", self.genesis_fork_version, 0),
+            ("altair", self.altair_fork_version, self.altair_fork_epoch),
+            (
+                "bellatrix",
+                self.bellatrix_fork_version,
+                self.bellatrix_fork_epoch,
+            ),
+            (
+                "capella",
+                self.capella_fork_version,
+                self.capella_fork_epoch,
+            ),
+            ("deneb", self.deneb_fork_version, self.deneb_fork_epoch),
+            (
+                "electra",
+                self.electra_fork_version,
+                self.electra_fork_epoch,
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, _, epoch)| *epoch != FAR_FUTURE_EPOCH)
+        .map(|(name, version, epoch)| ScheduledFork {
+            name,
+            version: version.to_be_bytes(),
+            epoch,
+        })
+        .collect()
+    }
+
+    pub fn fork_version_at_epoch(&self, epoch: u64) -> [u8; 4] {
+        self.fork_at_epoch(epoch).current_version
+    }
+
+    /// Fork of the beacon state at `epoch`, the last scheduled fork activated at or before it.
+    pub fn fork_at_epoch(&self, epoch: u64) -> Fork {
+        let schedule = self.fork_schedule();
+        let active = schedule
+            .iter()
+            .rposition(|fork| fork.epoch <= epoch)
+            .unwrap_or_default();
+        let previous = active.saturating_sub(1);
+
+        Fork {
+            previous_version: schedule[previous].version,
+            current_version: schedule[active].version,
+            epoch: schedule[active].epoch,
+        }
+    }
+
+    /// The first scheduled fork activated after `epoch`.
+    pub fn next_fork(&self, epoch: u64) -> Option<ScheduledFork> {
+        self.fork_schedule()
+            .into_iter()
+            .find(|fork| fork.epoch > epoch)
+    }
+
     pub fn is_minimal_preset(&self) -> bool {
         self.preset_base.to_lowercase() == "minimal"
     }
+
+    /// Verify the SSZ limits of the beacon block containers which are the same in all presets.
+    pub fn check_block_limits(&self) -> Result<()> {
+        let limits = [
+            (
+                "MAX_VALIDATORS_PER_COMMITTEE",
+                self.max_validators_per_committee,
+                MAX_VALIDATORS_PER_COMMITTEE,
+            ),
+            (
+                "MAX_PROPOSER_SLASHINGS",
+                self.max_proposer_slashings,
+                MAX_PROPOSER_SLASHINGS,
+            ),
+            (
+                "MAX_ATTESTER_SLASHINGS",
+                self.max_attester_slashings,
+                MAX_ATTESTER_SLASHINGS,
+            ),
+            ("MAX_ATTESTATIONS", self.max_attestations, MAX_ATTESTATIONS),
+            ("MAX_DEPOSITS", self.max_deposits, MAX_DEPOSITS),
+            (
+                "MAX_VOLUNTARY_EXITS",
+                self.max_voluntary_exits,
+                MAX_VOLUNTARY_EXITS,
+            ),
+            (
+                "MAX_BYTES_PER_TRANSACTION",
+                self.max_bytes_per_transaction,
+                MAX_BYTES_PER_TRANSACTION,
+            ),
+            (
+                "MAX_TRANSACTIONS_PER_PAYLOAD",
+                self.max_transactions_per_payload,
+                MAX_TRANSACTIONS_PER_PAYLOAD,
+            ),
+            (
+                "BYTES_PER_LOGS_BLOOM",
+                self.bytes_per_logs_bloom,
+                BYTES_PER_LOGS_BLOOM,
+            ),
+            (
+                "MAX_EXTRA_DATA_BYTES",
+                self.max_extra_data_bytes,
+                MAX_EXTRA_DATA_BYTES,
+            ),
+            (
+                "MAX_BLS_TO_EXECUTION_CHANGES",
+                self.max_bls_to_execution_changes,
+                MAX_BLS_TO_EXECUTION_CHANGES,
+            ),
+        ];
+        for (name, value, expected) in limits {
+            if value != expected as u64 {
+                anyhow::bail!("Unsupported {} {}, expected {}", name, value, expected);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn sync_committee_size(&self) -> usize {
+        self.sync_committee_size as usize
+    }
+
+    /// Size of the `aggregation_bits` of a sync committee contribution.
+    pub fn sync_committee_contribution_size(&self) -> usize {
+        self.sync_committee_size() / SYNC_COMMITTEE_SUBNET_COUNT
+    }
+
+    pub fn max_validators_per_committee(&self) -> usize {
+        self.max_validators_per_committee as usize
+    }
+
+    pub fn max_withdrawals_per_payload(&self) -> usize {
+        self.max_withdrawals_per_payload as usize
+    }
+
+    pub fn max_blob_commitments_per_block(&self) -> usize {
+        self.max_blob_commitments_per_block as usize
+    }
+
+    pub fn field_elements_per_blob(&self) -> usize {
+        self.field_elements_per_blob as usize
+    }
+
+    pub fn max_committees_per_slot(&self) -> usize {
+        self.max_committees_per_slot as usize
+    }
+
+    /// Minimum deposit amount in Gwei.
+    pub fn min_deposit_amount(&self) -> u64 {
+        self.min_deposit_amount
+    }
+
+    /// Maximum effective balance in Gwei of validators without compounding withdrawal
+    /// credentials.
+    pub fn max_effective_balance(&self) -> u64 {
+        self.max_effective_balance
+    }
+
+    pub fn electra_fork_version(&self) -> [u8; 4] {
+        self.electra_fork_version.to_be_bytes()
+    }
+
+    pub fn electra_fork_epoch(&self) -> u64 {
+        self.electra_fork_epoch
+    }
+
+    /// genesis_validators_root of the predefined networks which have started
+    pub fn genesis_validators_root(&self) -> Option<[u8; 32]> {
+        GENESIS_VALIDATORS_ROOTS
+            .get(self.config_name.as_str())
+            .copied()
+    }
 }
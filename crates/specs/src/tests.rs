@@ -5,6 +5,8 @@ fn minimal_specs_works() {
     let spec = Spec::new("minimal").unwrap();
     assert_eq!(spec.max_committees_per_slot, 4);
     assert_eq!(spec.config_name, "minimal");
+    assert_eq!(spec.max_attestations_electra, 8);
+    assert_eq!(spec.max_withdrawal_requests_per_payload, 2);
 }
 
 #[test]
@@ -12,6 +14,8 @@ fn mainnet_specs_works() {
     let spec = Spec::new("mainnet").unwrap();
     assert_eq!(spec.max_committees_per_slot, 64);
     assert_eq!(spec.config_name, "mainnet");
+    assert_eq!(spec.max_withdrawal_requests_per_payload, 16);
+    assert_eq!(spec.electra_fork_version(), [0x05, 0x00, 0x00, 0x00]);
 }
 
 #[test]
@@ -29,4 +33,98 @@ fn custom_network_config() {
     let spec = Spec::new("tests/custom_network_config.yaml").unwrap();
     assert_eq!(spec.config_name, "end-to-end");
     assert_eq!(spec.genesis_delay, 10);
+    // written before electra, which is then not scheduled
+    assert_eq!(spec.electra_fork_version(), [0x05, 0x00, 0x00, 0x00]);
+    assert_eq!(spec.electra_fork_epoch(), FAR_FUTURE_EPOCH);
+}
+
+#[test]
+fn genesis_validators_root_of_predefined_networks() {
+    let spec = Spec::new("mainnet").unwrap();
+    assert_eq!(
+        spec.genesis_validators_root().unwrap()[..4],
+        [0x4b, 0x36, 0x3d, 0xb9]
+    );
+    assert_eq!(
+        Spec::new("minimal").unwrap().genesis_validators_root(),
+        None
+    );
+}
+
+#[test]
+fn block_limits_of_predefined_networks() {
+    let mainnet = Spec::new("mainnet").unwrap();
+    mainnet.check_block_limits().unwrap();
+    assert_eq!(mainnet.sync_committee_size(), SYNC_COMMITTEE_SIZE_MAINNET);
+    assert_eq!(
+        mainnet.sync_committee_contribution_size(),
+        SYNC_COMMITTEE_CONT_SIZE_MAINNET
+    );
+    assert_eq!(
+        mainnet.max_blob_commitments_per_block(),
+        MAX_BLOB_COMMITMENTS_PER_BLOCK_MAINNET
+    );
+
+    let minimal = Spec::new("minimal").unwrap();
+    minimal.check_block_limits().unwrap();
+    assert_eq!(
+        minimal.max_withdrawals_per_payload(),
+        MAX_WITHDRAWALS_PER_PAYLOAD_MINIMAL
+    );
+    assert_eq!(
+        minimal.field_elements_per_blob(),
+        FIELD_ELEMENTS_PER_BLOB_MINIMAL
+    );
+}
+
+#[test]
+fn fork_schedule_of_mainnet() {
+    let spec = Spec::new("mainnet").unwrap();
+    assert_eq!(spec.fork_schedule().len(), 6);
+    assert_eq!(spec.fork_version_at_epoch(74239), [0, 0, 0, 0]);
+    assert_eq!(spec.fork_version_at_epoch(74240), [1, 0, 0, 0]);
+    assert_eq!(
+        spec.fork_at_epoch(200000),
+        Fork {
+            previous_version: [2, 0, 0, 0],
+            current_version: [3, 0, 0, 0],
+            epoch: 194048,
+        }
+    );
+    assert_eq!(
+        spec.next_fork(194048),
+        Some(ScheduledFork {
+            name: "deneb",
+            version: [4, 0, 0, 0],
+            epoch: 269568,
+        })
+    );
+    assert_eq!(spec.next_fork(364032), None);
+}
+
+#[test]
+fn unscheduled_forks_are_not_part_of_the_fork_schedule() {
+    let spec = Spec::new("minimal").unwrap();
+    assert_eq!(
+        spec.fork_at_epoch(1000),
+        Fork {
+            previous_version: [0, 0, 0, 1],
+            current_version: [0, 0, 0, 1],
+            epoch: 0,
+        }
+    );
+    assert_eq!(spec.next_fork(0), None);
+
+    // neither deneb nor electra are scheduled in the custom network
+    let spec = Spec::new("tests/custom_network_config.yaml").unwrap();
+    assert_eq!(
+        spec.fork_at_epoch(1000),
+        Fork {
+            previous_version: [0x02, 0, 0, 0xfd],
+            current_version: [0x03, 0, 0, 0xfd],
+            epoch: 10,
+        }
+    );
+    assert_eq!(spec.next_fork(9).unwrap().name, "capella");
+    assert_eq!(spec.next_fork(10), None);
 }